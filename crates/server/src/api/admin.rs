@@ -0,0 +1,51 @@
+use axum::Json;
+use axum::extract::{Path, State};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::error::ApiError;
+use crate::features::FeatureFlag;
+use crate::state::AppState;
+
+/// Effective server configuration with secrets masked.
+pub async fn config(State(state): State<AppState>) -> Json<Value> {
+    Json(state.config.masked())
+}
+
+pub async fn list_features(State(state): State<AppState>) -> Json<Vec<FeatureFlag>> {
+    Json(state.features.list())
+}
+
+#[derive(Deserialize)]
+pub struct SetFeatureRequest {
+    enabled: bool,
+}
+
+pub async fn set_feature(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<SetFeatureRequest>,
+) -> Result<Json<Vec<FeatureFlag>>, ApiError> {
+    ensure_declared(&state, &name)?;
+    state.features.set_override(&name, request.enabled);
+    tracing::info!(feature = %name, enabled = request.enabled, "feature flag overridden");
+    Ok(Json(state.features.list()))
+}
+
+pub async fn clear_feature(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<Vec<FeatureFlag>>, ApiError> {
+    ensure_declared(&state, &name)?;
+    state.features.clear_override(&name);
+    tracing::info!(feature = %name, "feature flag override cleared");
+    Ok(Json(state.features.list()))
+}
+
+fn ensure_declared(state: &AppState, name: &str) -> Result<(), ApiError> {
+    if state.features.is_declared(name) {
+        Ok(())
+    } else {
+        Err(ApiError::NotFound(format!("unknown feature flag: {name}")))
+    }
+}
@@ -0,0 +1,201 @@
+use axum::extract::{Query, Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+
+use super::error::ApiError;
+use crate::auth::{Principal, Scope};
+use crate::state::AppState;
+
+/// Query parameter accepted in place of the `Authorization` header, for
+/// clients such as browser WebSockets that cannot set headers. Parsed like
+/// any other query string, so percent-encoded tokens match.
+#[derive(Deserialize)]
+struct TokenQuery {
+    access_token: Option<String>,
+}
+
+/// Resolves the caller's token and stores the [`Principal`] in the request
+/// extensions for [`require_scope`] and handlers.
+pub async fn authenticate(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let principal = if state.auth.is_enabled() {
+        let Some(token) = request_token(&request) else {
+            return ApiError::Unauthorized("missing bearer token".into()).into_response();
+        };
+        match state.auth.authenticate(&token) {
+            Some(principal) => principal,
+            None => return ApiError::Unauthorized("invalid token".into()).into_response(),
+        }
+    } else {
+        Principal::anonymous()
+    };
+
+    request.extensions_mut().insert(principal);
+    next.run(request).await
+}
+
+/// Route layer rejecting callers without `scope`.
+pub async fn require_scope(State(scope): State<Scope>, request: Request, next: Next) -> Response {
+    let allowed = request
+        .extensions()
+        .get::<Principal>()
+        .is_some_and(|principal| principal.has_scope(scope));
+
+    if !allowed {
+        return ApiError::Forbidden(format!("missing scope {}", scope.as_str())).into_response();
+    }
+    next.run(request).await
+}
+
+fn request_token(request: &Request) -> Option<String> {
+    let header = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if let Some(token) = header {
+        return Some(token.trim().to_string());
+    }
+
+    Query::<TokenQuery>::try_from_uri(request.uri())
+        .ok()?
+        .0
+        .access_token
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::http::header::AUTHORIZATION;
+
+    use crate::auth::Scope;
+    use crate::config::AuthClient;
+    use crate::testing::{TestApp, app, request};
+
+    fn with_clients() -> TestApp {
+        app(|config| {
+            config.auth.clients = vec![
+                AuthClient {
+                    name: "reader".into(),
+                    token: "read+token/=".into(),
+                    scopes: vec![Scope::FsRead],
+                },
+                AuthClient {
+                    name: "admin".into(),
+                    token: "admin-token".into(),
+                    scopes: vec![Scope::Admin],
+                },
+            ];
+        })
+    }
+
+    async fn status(app: &TestApp, method: &str, uri: &str, token: Option<&str>) -> StatusCode {
+        let mut builder = request(method, uri);
+        if let Some(token) = token {
+            builder = builder.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        app.send(builder.body(Body::empty()).unwrap()).await.0
+    }
+
+    #[tokio::test]
+    async fn anonymous_callers_may_only_read() {
+        let app = app(|_| {});
+        let root = app.root.display().to_string();
+        let tree = format!("/api/fs/tree?path={root}");
+        let write = format!("/api/fs/file?path={root}/new");
+
+        assert_eq!(status(&app, "GET", &tree, None).await, StatusCode::OK);
+        assert_eq!(
+            status(&app, "PUT", &write, None).await,
+            StatusCode::FORBIDDEN
+        );
+        assert!(!app.root.join("new").exists());
+        for (method, uri) in [
+            ("GET", "/api/admin/config"),
+            ("GET", "/api/system/processes"),
+            ("POST", "/api/system/processes/1/kill"),
+            ("GET", "/api/terminal"),
+        ] {
+            assert_eq!(
+                status(&app, method, uri, None).await,
+                StatusCode::FORBIDDEN,
+                "{uri}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn configured_clients_must_present_a_valid_token() {
+        let app = with_clients();
+        let tree = format!("/api/fs/tree?path={}", app.root.display());
+
+        assert_eq!(
+            status(&app, "GET", &tree, None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&app, "GET", &tree, Some("wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&app, "GET", &tree, Some("read+token/=")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&app, "GET", "/api/health", None).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn tokens_are_accepted_percent_encoded_in_the_query() {
+        let app = with_clients();
+        let tree = format!("/api/fs/tree?path={}", app.root.display());
+
+        let uri = format!("{tree}&access_token=read%2Btoken%2F%3D");
+        assert_eq!(status(&app, "GET", &uri, None).await, StatusCode::OK);
+        let uri = format!("{tree}&access_token=read+token/=");
+        assert_eq!(
+            status(&app, "GET", &uri, None).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn routes_require_their_scope() {
+        let app = with_clients();
+        let write = format!("/api/fs/file?path={}/new", app.root.display());
+        let reader = Some("read+token/=");
+        let admin = Some("admin-token");
+
+        assert_eq!(
+            status(&app, "PUT", &write, reader).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(&app, "GET", "/api/admin/config", reader).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(&app, "GET", "/api/system/processes", reader).await,
+            StatusCode::FORBIDDEN
+        );
+
+        // Admin grants every other scope.
+        assert_eq!(status(&app, "PUT", &write, admin).await, StatusCode::OK);
+        assert_eq!(
+            status(&app, "GET", "/api/admin/config", admin).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&app, "GET", "/api/system/processes", admin).await,
+            StatusCode::OK
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use axum::Json;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde_json::json;
+use system_capabilities::CapabilityError;
+
+use crate::workspace::WorkspaceError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    Unauthorized(String),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    PayloadTooLarge(String),
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+impl ApiError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if let ApiError::Internal(message) = &self {
+            tracing::error!("{message}");
+        }
+        (self.status(), Json(json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+impl From<CapabilityError> for ApiError {
+    fn from(err: CapabilityError) -> Self {
+        match err {
+            CapabilityError::NotFound(_) | CapabilityError::ProcessNotFound(_) => {
+                ApiError::NotFound(err.to_string())
+            }
+            CapabilityError::OutsideAllowedRoots(_)
+            | CapabilityError::ProtectedRoot(_)
+            | CapabilityError::ProcessNotOwned(_) => ApiError::Forbidden(err.to_string()),
+            CapabilityError::AlreadyExists(_) | CapabilityError::TerminalLimit { .. } => {
+                ApiError::Conflict(err.to_string())
+            }
+            CapabilityError::TooLarge { .. } => ApiError::PayloadTooLarge(err.to_string()),
+            CapabilityError::NotAFile(_)
+            | CapabilityError::NotARepository(_)
+            | CapabilityError::Git(_)
+            | CapabilityError::InvalidArgument(_) => ApiError::BadRequest(err.to_string()),
+            CapabilityError::Terminal(_) | CapabilityError::Io(_) => {
+                ApiError::Internal(err.to_string())
+            }
+        }
+    }
+}
+
+impl From<WorkspaceError> for ApiError {
+    fn from(err: WorkspaceError) -> Self {
+        match err {
+            WorkspaceError::NotFound(_) => ApiError::NotFound(err.to_string()),
+            WorkspaceError::DuplicatePath(_) => ApiError::Conflict(err.to_string()),
+            WorkspaceError::Read { .. }
+            | WorkspaceError::Parse { .. }
+            | WorkspaceError::Write { .. } => ApiError::Internal(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,427 @@
+use std::convert::Infallible;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::Json;
+use axum::body::{Body, Bytes};
+use axum::extract::{Query, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+use system_capabilities::fs::{
+    DeleteOutcome, DirectorySizeEvent, DuplicateOptions, DuplicateReport, FileContent, FileHash,
+    FileInfo, FileSystemEntry, HashAlgorithm, ProjectInfo, RecentFiles, SearchEvent, SearchOptions,
+    SearchSummary, TrashEntry, WriteOptions,
+};
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::ReceiverStream;
+
+use super::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct ReadQuery {
+    path: PathBuf,
+    #[serde(default)]
+    offset: u64,
+    limit: Option<u64>,
+}
+
+pub async fn read(
+    State(state): State<AppState>,
+    Query(query): Query<ReadQuery>,
+) -> Result<Json<FileContent>, ApiError> {
+    let content = state
+        .fs
+        .read_file(&query.path, query.offset, query.limit)
+        .await?;
+    Ok(Json(content))
+}
+
+const DEFAULT_TREE_DEPTH: usize = 2;
+const MAX_TREE_DEPTH: usize = 8;
+const DEFAULT_TREE_ENTRIES: usize = 2000;
+const MAX_TREE_ENTRIES: usize = 10_000;
+
+#[derive(Deserialize)]
+pub struct TreeQuery {
+    path: PathBuf,
+    max_depth: Option<usize>,
+    max_entries: Option<usize>,
+}
+
+pub async fn tree(
+    State(state): State<AppState>,
+    Query(query): Query<TreeQuery>,
+) -> Result<Json<FileSystemEntry>, ApiError> {
+    let max_depth = query
+        .max_depth
+        .unwrap_or(DEFAULT_TREE_DEPTH)
+        .min(MAX_TREE_DEPTH);
+    let max_entries = query
+        .max_entries
+        .unwrap_or(DEFAULT_TREE_ENTRIES)
+        .min(MAX_TREE_ENTRIES);
+    let tree = state
+        .fs
+        .read_tree(&query.path, max_depth, max_entries)
+        .await?;
+    Ok(Json(tree))
+}
+
+#[derive(Deserialize)]
+pub struct WriteQuery {
+    path: PathBuf,
+    #[serde(default)]
+    overwrite: bool,
+    #[serde(default)]
+    create_parents: bool,
+}
+
+/// Writes the raw request body to `path`.
+pub async fn write(
+    State(state): State<AppState>,
+    Query(query): Query<WriteQuery>,
+    body: Bytes,
+) -> Result<Json<FileInfo>, ApiError> {
+    let options = WriteOptions {
+        overwrite: query.overwrite,
+        create_parents: query.create_parents,
+    };
+    let info = state.fs.write_file(&query.path, &body, options).await?;
+    Ok(Json(info))
+}
+
+#[derive(Deserialize)]
+pub struct MkdirRequest {
+    path: PathBuf,
+    #[serde(default)]
+    recursive: bool,
+}
+
+pub async fn mkdir(
+    State(state): State<AppState>,
+    Json(request): Json<MkdirRequest>,
+) -> Result<Json<FileInfo>, ApiError> {
+    let info = state
+        .fs
+        .create_directory(&request.path, request.recursive)
+        .await?;
+    Ok(Json(info))
+}
+
+#[derive(Deserialize)]
+pub struct RenameRequest {
+    from: PathBuf,
+    to: PathBuf,
+    #[serde(default)]
+    overwrite: bool,
+}
+
+pub async fn rename(
+    State(state): State<AppState>,
+    Json(request): Json<RenameRequest>,
+) -> Result<Json<FileInfo>, ApiError> {
+    let info = state
+        .fs
+        .rename(&request.from, &request.to, request.overwrite)
+        .await?;
+    Ok(Json(info))
+}
+
+#[derive(Deserialize)]
+pub struct DeleteQuery {
+    path: PathBuf,
+    #[serde(default)]
+    recursive: bool,
+    #[serde(default)]
+    trash: bool,
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    Query(query): Query<DeleteQuery>,
+) -> Result<Json<DeleteOutcome>, ApiError> {
+    let outcome = state
+        .fs
+        .delete(&query.path, query.recursive, query.trash)
+        .await?;
+    Ok(Json(outcome))
+}
+
+#[derive(Deserialize)]
+pub struct TrashQuery {
+    /// Selects the trash that deletions under this path go to; defaults to
+    /// that of every allowed root.
+    path: Option<PathBuf>,
+}
+
+/// Trashed entries, newest first.
+pub async fn list_trash(
+    State(state): State<AppState>,
+    Query(query): Query<TrashQuery>,
+) -> Result<Json<Vec<TrashEntry>>, ApiError> {
+    Ok(Json(state.fs.list_trash(query.path.as_deref()).await?))
+}
+
+#[derive(Deserialize)]
+pub struct RestoreRequest {
+    id: String,
+    path: Option<PathBuf>,
+    /// Restores somewhere other than the original location.
+    to: Option<PathBuf>,
+}
+
+pub async fn restore_trash(
+    State(state): State<AppState>,
+    Json(request): Json<RestoreRequest>,
+) -> Result<Json<FileInfo>, ApiError> {
+    let info = state
+        .fs
+        .restore_from_trash(request.path.as_deref(), &request.id, request.to.as_deref())
+        .await?;
+    Ok(Json(info))
+}
+
+#[derive(Deserialize)]
+pub struct PurgeQuery {
+    path: Option<PathBuf>,
+    /// Purges a single entry; without it the whole trash is emptied.
+    id: Option<String>,
+}
+
+pub async fn purge_trash(
+    State(state): State<AppState>,
+    Query(query): Query<PurgeQuery>,
+) -> Result<Json<Vec<TrashEntry>>, ApiError> {
+    let purged = state
+        .fs
+        .purge_trash(query.path.as_deref(), query.id.as_deref())
+        .await?;
+    Ok(Json(purged))
+}
+
+/// Default and maximum number of matches a search returns.
+const MAX_SEARCH_RESULTS: usize = 1000;
+const DEFAULT_SEARCH_BUDGET_MS: u64 = 10_000;
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    path: PathBuf,
+    query: String,
+    limit: Option<usize>,
+    max_depth: Option<usize>,
+    #[serde(default)]
+    include_hidden: bool,
+    budget_ms: Option<u64>,
+}
+
+impl SearchQuery {
+    fn into_parts(self) -> (PathBuf, SearchOptions) {
+        let options = SearchOptions {
+            query: self.query,
+            max_results: self
+                .limit
+                .unwrap_or(MAX_SEARCH_RESULTS)
+                .min(MAX_SEARCH_RESULTS),
+            max_depth: self.max_depth,
+            include_hidden: self.include_hidden,
+            budget: Duration::from_millis(self.budget_ms.unwrap_or(DEFAULT_SEARCH_BUDGET_MS)),
+        };
+        (self.path, options)
+    }
+}
+
+#[derive(Serialize)]
+pub struct SearchResponse {
+    matches: Vec<FileInfo>,
+    summary: Option<SearchSummary>,
+}
+
+/// Runs a search to completion and returns every match at once.
+pub async fn search(
+    State(state): State<AppState>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, ApiError> {
+    let (path, options) = query.into_parts();
+    let mut events = state.fs.search(&path, options).await?;
+
+    let mut response = SearchResponse {
+        matches: Vec::new(),
+        summary: None,
+    };
+    while let Some(event) = events.recv().await {
+        match event {
+            SearchEvent::Match(info) => response.matches.push(info),
+            SearchEvent::Summary(summary) => response.summary = Some(summary),
+        }
+    }
+    Ok(Json(response))
+}
+
+/// Streams search matches as newline-delimited JSON while the walk runs,
+/// ending with a summary record. Disconnecting stops the walk.
+pub async fn search_stream(
+    State(state): State<AppState>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Response, ApiError> {
+    let (path, options) = query.into_parts();
+    let events = state.fs.search(&path, options).await?;
+    Ok(ndjson(events))
+}
+
+const DEFAULT_SIZE_BUDGET_MS: u64 = 10_000;
+
+#[derive(Deserialize)]
+pub struct SizeQuery {
+    path: PathBuf,
+    budget_ms: Option<u64>,
+}
+
+impl SizeQuery {
+    fn budget(&self) -> Duration {
+        Duration::from_millis(self.budget_ms.unwrap_or(DEFAULT_SIZE_BUDGET_MS))
+    }
+}
+
+/// Returns the final size record once the walk finishes or runs out of
+/// time.
+pub async fn size(
+    State(state): State<AppState>,
+    Query(query): Query<SizeQuery>,
+) -> Result<Json<DirectorySizeEvent>, ApiError> {
+    let mut events = state.fs.directory_size(&query.path, query.budget()).await?;
+    let mut last = None;
+    while let Some(event) = events.recv().await {
+        last = Some(event);
+    }
+    last.map(Json)
+        .ok_or_else(|| ApiError::Internal("directory size walk ended without a result".into()))
+}
+
+/// Streams running totals as newline-delimited JSON, ending with the final
+/// record.
+pub async fn size_stream(
+    State(state): State<AppState>,
+    Query(query): Query<SizeQuery>,
+) -> Result<Response, ApiError> {
+    let events = state.fs.directory_size(&query.path, query.budget()).await?;
+    Ok(ndjson(events))
+}
+
+#[derive(Deserialize)]
+pub struct ProjectQuery {
+    path: PathBuf,
+}
+
+/// Languages, build tools and version control detected in a directory.
+pub async fn project(
+    State(state): State<AppState>,
+    Query(query): Query<ProjectQuery>,
+) -> Result<Json<ProjectInfo>, ApiError> {
+    Ok(Json(state.fs.detect_project(&query.path).await?))
+}
+
+#[derive(Deserialize)]
+pub struct HashQuery {
+    path: PathBuf,
+    #[serde(default)]
+    algo: HashAlgorithm,
+}
+
+pub async fn hash(
+    State(state): State<AppState>,
+    Query(query): Query<HashQuery>,
+) -> Result<Json<FileHash>, ApiError> {
+    Ok(Json(state.fs.hash_file(&query.path, query.algo).await?))
+}
+
+/// Default and maximum number of files a duplicate scan considers.
+const MAX_DUPLICATE_FILES: usize = 100_000;
+const DEFAULT_DUPLICATES_BUDGET_MS: u64 = 30_000;
+
+#[derive(Deserialize)]
+pub struct DuplicatesQuery {
+    path: PathBuf,
+    #[serde(default)]
+    algo: HashAlgorithm,
+    #[serde(default)]
+    min_size: u64,
+    max_depth: Option<usize>,
+    #[serde(default)]
+    include_hidden: bool,
+    max_files: Option<usize>,
+    budget_ms: Option<u64>,
+}
+
+/// Groups of files under `path` with identical content.
+pub async fn duplicates(
+    State(state): State<AppState>,
+    Query(query): Query<DuplicatesQuery>,
+) -> Result<Json<DuplicateReport>, ApiError> {
+    let options = DuplicateOptions {
+        algorithm: query.algo,
+        min_size: query.min_size,
+        max_depth: query.max_depth,
+        include_hidden: query.include_hidden,
+        max_files: query
+            .max_files
+            .unwrap_or(MAX_DUPLICATE_FILES)
+            .min(MAX_DUPLICATE_FILES),
+        budget: Duration::from_millis(query.budget_ms.unwrap_or(DEFAULT_DUPLICATES_BUDGET_MS)),
+    };
+    Ok(Json(state.fs.find_duplicates(&query.path, options).await?))
+}
+
+/// Default look-back window and default and maximum number of files for
+/// the recent files listing.
+const DEFAULT_RECENT_WINDOW_SECS: u64 = 5 * 60;
+const DEFAULT_RECENT_LIMIT: usize = 100;
+const MAX_RECENT_LIMIT: usize = 1000;
+
+#[derive(Deserialize)]
+pub struct RecentQuery {
+    path: PathBuf,
+    /// Seconds since the Unix epoch; defaults to five minutes ago.
+    since: Option<u64>,
+    limit: Option<usize>,
+}
+
+/// Files modified after `since`, most recent first.
+pub async fn recent(
+    State(state): State<AppState>,
+    Query(query): Query<RecentQuery>,
+) -> Result<Json<RecentFiles>, ApiError> {
+    let since = query.since.unwrap_or_else(|| {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        now.saturating_sub(DEFAULT_RECENT_WINDOW_SECS)
+    });
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_RECENT_LIMIT)
+        .min(MAX_RECENT_LIMIT);
+    Ok(Json(
+        state.fs.recent_files(&query.path, since, limit).await?,
+    ))
+}
+
+/// Serves a channel of records as newline-delimited JSON. Dropping the
+/// response (e.g. on client disconnect) drops the receiver, which stops the
+/// producer.
+fn ndjson<T: Serialize + Send + 'static>(events: mpsc::Receiver<T>) -> Response {
+    let lines = ReceiverStream::new(events).map(|event| {
+        let mut line = serde_json::to_vec(&event).unwrap_or_default();
+        line.push(b'\n');
+        Ok::<_, Infallible>(line)
+    });
+
+    (
+        [(CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+        .into_response()
+}
@@ -0,0 +1,174 @@
+use std::path::{Path, PathBuf};
+
+use axum::Json;
+use axum::extract::{Query, State};
+use serde::Deserialize;
+use system_capabilities::GitRepository;
+use system_capabilities::git::{
+    BranchInfo, CommitAuthor, CommitInfo, GitDiff, GitStatus, RepositoryInfo,
+};
+
+use super::error::ApiError;
+use crate::state::AppState;
+
+const DEFAULT_LOG_LIMIT: usize = 20;
+const MAX_LOG_LIMIT: usize = 500;
+
+#[derive(Deserialize)]
+pub struct RepoQuery {
+    path: PathBuf,
+}
+
+/// Opens the repository containing `path`, requiring both the path and the
+/// repository root to lie within the allowed roots.
+async fn open(state: &AppState, path: &Path) -> Result<GitRepository, ApiError> {
+    let path = state.fs.resolve(path).await?;
+    let repo = GitRepository::discover(&path).await?;
+    state.fs.resolve(repo.root()).await?;
+    Ok(repo)
+}
+
+pub async fn repository(
+    State(state): State<AppState>,
+    Query(query): Query<RepoQuery>,
+) -> Result<Json<RepositoryInfo>, ApiError> {
+    let repo = open(&state, &query.path).await?;
+    Ok(Json(repo.info().await?))
+}
+
+pub async fn status(
+    State(state): State<AppState>,
+    Query(query): Query<RepoQuery>,
+) -> Result<Json<GitStatus>, ApiError> {
+    let repo = open(&state, &query.path).await?;
+    Ok(Json(repo.status().await?))
+}
+
+#[derive(Deserialize)]
+pub struct LogQuery {
+    path: PathBuf,
+    limit: Option<usize>,
+}
+
+pub async fn log(
+    State(state): State<AppState>,
+    Query(query): Query<LogQuery>,
+) -> Result<Json<Vec<CommitInfo>>, ApiError> {
+    let repo = open(&state, &query.path).await?;
+    let limit = query.limit.unwrap_or(DEFAULT_LOG_LIMIT).min(MAX_LOG_LIMIT);
+    Ok(Json(repo.log(limit).await?))
+}
+
+#[derive(Deserialize)]
+pub struct DiffQuery {
+    path: PathBuf,
+    #[serde(default)]
+    staged: bool,
+    /// Limit the diff to one file, relative to the repository root.
+    file: Option<PathBuf>,
+}
+
+pub async fn diff(
+    State(state): State<AppState>,
+    Query(query): Query<DiffQuery>,
+) -> Result<Json<GitDiff>, ApiError> {
+    let repo = open(&state, &query.path).await?;
+    let files: Vec<PathBuf> = query.file.into_iter().collect();
+    Ok(Json(repo.diff(query.staged, &files).await?))
+}
+
+pub async fn branches(
+    State(state): State<AppState>,
+    Query(query): Query<RepoQuery>,
+) -> Result<Json<Vec<BranchInfo>>, ApiError> {
+    let repo = open(&state, &query.path).await?;
+    Ok(Json(repo.branches().await?))
+}
+
+#[derive(Deserialize)]
+pub struct PathsRequest {
+    path: PathBuf,
+    /// Files relative to the repository root.
+    files: Vec<PathBuf>,
+}
+
+pub async fn stage(
+    State(state): State<AppState>,
+    Json(request): Json<PathsRequest>,
+) -> Result<Json<GitStatus>, ApiError> {
+    let repo = open(&state, &request.path).await?;
+    repo.stage(&request.files).await?;
+    Ok(Json(repo.status().await?))
+}
+
+pub async fn unstage(
+    State(state): State<AppState>,
+    Json(request): Json<PathsRequest>,
+) -> Result<Json<GitStatus>, ApiError> {
+    let repo = open(&state, &request.path).await?;
+    repo.unstage(&request.files).await?;
+    Ok(Json(repo.status().await?))
+}
+
+#[derive(Deserialize)]
+pub struct CommitRequest {
+    path: PathBuf,
+    message: String,
+    author_name: Option<String>,
+    author_email: Option<String>,
+}
+
+pub async fn commit(
+    State(state): State<AppState>,
+    Json(request): Json<CommitRequest>,
+) -> Result<Json<CommitInfo>, ApiError> {
+    let repo = open(&state, &request.path).await?;
+    let author = match (request.author_name, request.author_email) {
+        (Some(name), Some(email)) => Some(CommitAuthor { name, email }),
+        (None, None) => None,
+        _ => {
+            return Err(ApiError::BadRequest(
+                "author_name and author_email must be given together".into(),
+            ));
+        }
+    };
+    Ok(Json(repo.commit(&request.message, author.as_ref()).await?))
+}
+
+#[derive(Deserialize)]
+pub struct CreateBranchRequest {
+    path: PathBuf,
+    name: String,
+    start_point: Option<String>,
+    #[serde(default)]
+    switch: bool,
+}
+
+pub async fn create_branch(
+    State(state): State<AppState>,
+    Json(request): Json<CreateBranchRequest>,
+) -> Result<Json<Vec<BranchInfo>>, ApiError> {
+    let repo = open(&state, &request.path).await?;
+    repo.create_branch(
+        &request.name,
+        request.start_point.as_deref(),
+        request.switch,
+    )
+    .await?;
+    Ok(Json(repo.branches().await?))
+}
+
+#[derive(Deserialize)]
+pub struct SwitchRequest {
+    path: PathBuf,
+    branch: String,
+}
+
+pub async fn switch(
+    State(state): State<AppState>,
+    Json(request): Json<SwitchRequest>,
+) -> Result<Json<Vec<BranchInfo>>, ApiError> {
+    let repo = open(&state, &request.path).await?;
+    repo.switch_branch(&request.branch).await?;
+    Ok(Json(repo.branches().await?))
+}
@@ -0,0 +1,34 @@
+use axum::Json;
+use axum::extract::State;
+use serde::Serialize;
+
+use crate::state::AppState;
+
+#[derive(Serialize)]
+pub struct HealthResponse {
+    status: &'static str,
+    version: &'static str,
+}
+
+pub async fn health() -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: "ok",
+        version: env!("CARGO_PKG_VERSION"),
+    })
+}
+
+/// What this server instance supports, for clients to adapt their UI.
+#[derive(Serialize)]
+pub struct CapabilitiesResponse {
+    version: &'static str,
+    read_only: bool,
+    features: Vec<String>,
+}
+
+pub async fn capabilities(State(state): State<AppState>) -> Json<CapabilitiesResponse> {
+    Json(CapabilitiesResponse {
+        version: env!("CARGO_PKG_VERSION"),
+        read_only: state.config.read_only,
+        features: state.features.enabled(),
+    })
+}
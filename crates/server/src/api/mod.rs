@@ -0,0 +1,152 @@
+use axum::extract::DefaultBodyLimit;
+use axum::routing::{delete, get, post, put};
+use axum::{Router, middleware};
+
+use crate::auth::Scope;
+use crate::state::AppState;
+
+mod admin;
+mod auth;
+mod error;
+mod fs;
+mod git;
+mod health;
+mod origin;
+mod read_only;
+mod system;
+mod terminal;
+mod transfer;
+mod watch;
+mod workspaces;
+
+pub use error::ApiError;
+
+/// Routes mounted under `/api`.
+pub fn router(state: AppState) -> Router<AppState> {
+    let admin = Router::new()
+        .route("/config", get(admin::config))
+        .route("/features", get(admin::list_features))
+        .route(
+            "/features/{name}",
+            put(admin::set_feature).delete(admin::clear_feature),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            Scope::Admin,
+            auth::require_scope,
+        ));
+
+    let fs_read = Router::new()
+        .route("/read", get(fs::read))
+        .route("/tree", get(fs::tree))
+        .route("/download", get(transfer::download))
+        .route("/search", get(fs::search))
+        .route("/search/stream", get(fs::search_stream))
+        .route("/size", get(fs::size))
+        .route("/size/stream", get(fs::size_stream))
+        .route("/project", get(fs::project))
+        .route("/trash", get(fs::list_trash))
+        .route("/hash", get(fs::hash))
+        .route("/duplicates", get(fs::duplicates))
+        .route("/recent", get(fs::recent))
+        .route("/watch", get(watch::watch))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::FsRead,
+            auth::require_scope,
+        ));
+
+    let git_read = Router::new()
+        .route("/repository", get(git::repository))
+        .route("/status", get(git::status))
+        .route("/log", get(git::log))
+        .route("/diff", get(git::diff))
+        .route("/branches", get(git::branches))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::FsRead,
+            auth::require_scope,
+        ));
+
+    let max_write_bytes = usize::try_from(state.config.fs.max_write_bytes).unwrap_or(usize::MAX);
+    let max_upload_bytes = usize::try_from(state.config.fs.max_upload_bytes).unwrap_or(usize::MAX);
+    let fs_write = Router::new()
+        .route(
+            "/file",
+            put(fs::write).layer(DefaultBodyLimit::max(max_write_bytes)),
+        )
+        .route(
+            "/upload",
+            post(transfer::upload).layer(DefaultBodyLimit::max(max_upload_bytes)),
+        )
+        .route("/mkdir", post(fs::mkdir))
+        .route("/rename", post(fs::rename))
+        .route("/entry", delete(fs::delete))
+        .route("/trash", delete(fs::purge_trash))
+        .route("/trash/restore", post(fs::restore_trash))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::FsWrite,
+            auth::require_scope,
+        ));
+
+    let git_write = Router::new()
+        .route("/stage", post(git::stage))
+        .route("/unstage", post(git::unstage))
+        .route("/commit", post(git::commit))
+        .route("/branches", post(git::create_branch))
+        .route("/switch", post(git::switch))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::FsWrite,
+            auth::require_scope,
+        ));
+
+    let system_admin = Router::new()
+        .route("/processes", get(system::processes))
+        .route("/processes/{pid}/kill", post(system::kill_process))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::Admin,
+            auth::require_scope,
+        ));
+
+    let workspaces_read = Router::new()
+        .route("/", get(workspaces::list))
+        .route("/recent", get(workspaces::recent))
+        .route("/{id}", get(workspaces::get))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::FsRead,
+            auth::require_scope,
+        ));
+
+    let workspaces_write = Router::new()
+        .route("/", post(workspaces::create))
+        .route("/{id}", put(workspaces::update).delete(workspaces::delete))
+        .route("/{id}/open", post(workspaces::open))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::FsWrite,
+            auth::require_scope,
+        ));
+
+    let terminal = Router::new()
+        .route("/terminal", get(terminal::terminal))
+        .route_layer(middleware::from_fn_with_state(
+            Scope::Admin,
+            auth::require_scope,
+        ));
+
+    Router::new()
+        .route("/capabilities", get(health::capabilities))
+        .route("/system/info", get(system::info))
+        .nest("/admin", admin)
+        .nest("/fs", fs_read.merge(fs_write))
+        .nest("/git", git_read.merge(git_write))
+        .nest("/system", system_admin)
+        .nest("/workspaces", workspaces_read.merge(workspaces_write))
+        .merge(terminal)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            read_only::enforce,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::authenticate,
+        ))
+        .layer(middleware::from_fn_with_state(state, origin::verify))
+        .route("/health", get(health::health))
+}
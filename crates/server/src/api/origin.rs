@@ -0,0 +1,164 @@
+use axum::extract::{Request, State};
+use axum::http::header::{HOST, ORIGIN, UPGRADE};
+use axum::http::{HeaderMap, Method};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use super::error::ApiError;
+use crate::state::AppState;
+
+/// Rejects mutating requests and WebSocket upgrades sent by web pages on
+/// other origins. Browsers send some mutating requests, such as multipart
+/// form posts and body-less POSTs, cross-site without a CORS preflight, and
+/// WebSockets are never subject to CORS, so any page the user visits could
+/// otherwise act with the access the server grants its callers, up to
+/// driving a terminal. Requests without an `Origin` header come from
+/// non-browser clients and pass.
+pub async fn verify(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    if (is_mutating(request.method()) || is_websocket_upgrade(request.headers()))
+        && !is_allowed(request.headers(), &state.config.auth.allowed_origins)
+    {
+        return ApiError::Forbidden("cross-origin request refused".into()).into_response();
+    }
+    next.run(request).await
+}
+
+fn is_mutating(method: &Method) -> bool {
+    !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+fn is_websocket_upgrade(headers: &HeaderMap) -> bool {
+    headers
+        .get(UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+}
+
+/// Whether the request's `Origin`, if any, is the server's own origin or
+/// one of the configured `allowed` origins.
+fn is_allowed(headers: &HeaderMap, allowed: &[String]) -> bool {
+    let Some(origin) = headers.get(ORIGIN) else {
+        return true;
+    };
+    let Ok(origin) = origin.to_str() else {
+        return false;
+    };
+    if allowed.iter().any(|allowed| allowed == origin) {
+        return true;
+    }
+    let host = headers.get(HOST).and_then(|host| host.to_str().ok());
+    let authority = origin.split_once("://").map(|(_, authority)| authority);
+    host.is_some() && authority == host
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::http::header::{AUTHORIZATION, CONNECTION, HOST, ORIGIN, UPGRADE};
+
+    use crate::auth::Scope;
+    use crate::config::AuthClient;
+    use crate::testing::{TestApp, app, request};
+
+    fn admin_app() -> TestApp {
+        app(|config| {
+            config.auth.clients = vec![AuthClient {
+                name: "admin".into(),
+                token: "admin-token".into(),
+                scopes: vec![Scope::Admin],
+            }];
+            config.auth.allowed_origins = vec!["http://localhost:5173".into()];
+        })
+    }
+
+    async fn status(app: &TestApp, method: &str, uri: &str, origin: Option<&str>) -> StatusCode {
+        let mut builder = request(method, uri)
+            .header(HOST, "127.0.0.1:3000")
+            .header(AUTHORIZATION, "Bearer admin-token");
+        if let Some(origin) = origin {
+            builder = builder.header(ORIGIN, origin);
+        }
+        app.send(builder.body(Body::empty()).unwrap()).await.0
+    }
+
+    #[tokio::test]
+    async fn foreign_origins_cannot_mutate() {
+        let app = admin_app();
+        let root = app.root.display().to_string();
+        let evil = Some("https://evil.example");
+
+        let upload = format!("/api/fs/upload?path={root}&overwrite=true");
+        let write = format!("/api/fs/file?path={root}/a.txt");
+        for (method, uri) in [
+            ("POST", upload.as_str()),
+            ("PUT", write.as_str()),
+            ("POST", "/api/system/processes/1/kill"),
+        ] {
+            assert_eq!(
+                status(&app, method, uri, evil).await,
+                StatusCode::FORBIDDEN,
+                "{uri}"
+            );
+        }
+        assert_eq!(
+            status(&app, "PUT", &write, Some("null")).await,
+            StatusCode::FORBIDDEN
+        );
+        assert!(!app.root.join("a.txt").exists());
+
+        // Reads cannot be observed cross-origin without CORS, so they pass.
+        let tree = format!("/api/fs/tree?path={root}");
+        assert_eq!(status(&app, "GET", &tree, evil).await, StatusCode::OK);
+    }
+
+    async fn upgrade_status(app: &TestApp, uri: &str, origin: &str) -> StatusCode {
+        let request = request("GET", uri)
+            .header(HOST, "127.0.0.1:3000")
+            .header(AUTHORIZATION, "Bearer admin-token")
+            .header(ORIGIN, origin)
+            .header(CONNECTION, "upgrade")
+            .header(UPGRADE, "websocket")
+            .header("sec-websocket-version", "13")
+            .header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")
+            .body(Body::empty())
+            .unwrap();
+        app.send(request).await.0
+    }
+
+    #[tokio::test]
+    async fn foreign_origins_cannot_open_websockets() {
+        let app = admin_app();
+        let watch = format!("/api/fs/watch?path={}", app.root.display());
+
+        for uri in ["/api/terminal", watch.as_str()] {
+            let evil = upgrade_status(&app, uri, "https://evil.example").await;
+            assert_eq!(evil, StatusCode::FORBIDDEN, "{uri}");
+            // The upgrade itself cannot complete without a real connection;
+            // all that matters here is that the origin check let it through.
+            let own = upgrade_status(&app, uri, "http://127.0.0.1:3000").await;
+            assert_ne!(own, StatusCode::FORBIDDEN, "{uri}");
+            let allowed = upgrade_status(&app, uri, "http://localhost:5173").await;
+            assert_ne!(allowed, StatusCode::FORBIDDEN, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn own_and_allowed_origins_can_mutate() {
+        let app = admin_app();
+        let root = app.root.display().to_string();
+
+        for (name, origin) in [
+            ("a", None),
+            ("b", Some("http://127.0.0.1:3000")),
+            ("c", Some("http://localhost:5173")),
+        ] {
+            let write = format!("/api/fs/file?path={root}/{name}.txt");
+            assert_eq!(
+                status(&app, "PUT", &write, origin).await,
+                StatusCode::OK,
+                "{origin:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use axum::extract::{Request, State};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use super::error::ApiError;
+use crate::state::AppState;
+
+/// Routes, relative to `/api`, served in read-only demo mode. A public demo
+/// shows the UI without exposing the host, and nearly every other route,
+/// reads included, reveals the filesystem or spends its compute.
+const DEMO_ROUTES: &[&str] = &["/capabilities"];
+
+/// Rejects every request outside [`DEMO_ROUTES`] when the server runs in
+/// read-only demo mode.
+pub async fn enforce(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    if state.config.read_only && !is_demo_route(&request) {
+        return ApiError::Forbidden("server is running in read-only mode".into()).into_response();
+    }
+    next.run(request).await
+}
+
+fn is_demo_route(request: &Request) -> bool {
+    matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    ) && DEMO_ROUTES.contains(&request.uri().path())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::http::header::AUTHORIZATION;
+
+    use crate::auth::Scope;
+    use crate::config::AuthClient;
+    use crate::testing::{app, request};
+
+    #[tokio::test]
+    async fn read_only_mode_serves_only_demo_routes() {
+        let app = app(|config| config.read_only = true);
+        let root = app.root.display().to_string();
+        std::fs::write(app.root.join("a.txt"), "a").unwrap();
+
+        let (status, body) = app.get("/api/capabilities").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["read_only"], true);
+        assert_eq!(app.get("/api/health").await.0, StatusCode::OK);
+
+        for uri in [
+            format!("/api/fs/read?path={root}/a.txt"),
+            format!("/api/fs/download?path={root}/a.txt"),
+            format!("/api/fs/duplicates?path={root}"),
+            format!("/api/git/log?path={root}"),
+            "/api/system/processes".to_string(),
+            "/api/system/info".to_string(),
+            "/api/admin/config".to_string(),
+            "/api/workspaces".to_string(),
+        ] {
+            assert_eq!(app.get(&uri).await.0, StatusCode::FORBIDDEN, "{uri}");
+        }
+        let write = request("PUT", &format!("/api/fs/file?path={root}/b.txt"))
+            .body(Body::from("b"))
+            .unwrap();
+        assert_eq!(app.send(write).await.0, StatusCode::FORBIDDEN);
+        assert!(!app.root.join("b.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn normal_mode_serves_reads_and_writes() {
+        let app = app(|config| {
+            config.auth.clients = vec![AuthClient {
+                name: "admin".into(),
+                token: "admin-token".into(),
+                scopes: vec![Scope::Admin],
+            }];
+        });
+        let root = app.root.display().to_string();
+
+        let write = request("PUT", &format!("/api/fs/file?path={root}/b.txt"))
+            .header(AUTHORIZATION, "Bearer admin-token")
+            .body(Body::from("b"))
+            .unwrap();
+        assert_eq!(app.send(write).await.0, StatusCode::OK);
+        let read = request("GET", &format!("/api/fs/read?path={root}/b.txt"))
+            .header(AUTHORIZATION, "Bearer admin-token")
+            .body(Body::empty())
+            .unwrap();
+        let (status, body) = app.send(read).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["content"], "b");
+    }
+}
@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use serde::Deserialize;
+use system_capabilities::process::ProcessInfo;
+use system_capabilities::system_info::{self, SystemInfo};
+
+use super::error::ApiError;
+use crate::state::AppState;
+
+/// Host resources, with disk space reported for every allowed root (or the
+/// filesystem root when access is unrestricted).
+pub async fn info(State(state): State<AppState>) -> Result<Json<SystemInfo>, ApiError> {
+    let mut roots = state.fs.allowed_roots().to_vec();
+    if roots.is_empty() {
+        roots.push(PathBuf::from("/"));
+    }
+    Ok(Json(system_info::collect(roots).await?))
+}
+
+pub async fn processes(State(state): State<AppState>) -> Result<Json<Vec<ProcessInfo>>, ApiError> {
+    Ok(Json(state.processes.list().await?))
+}
+
+#[derive(Deserialize)]
+pub struct KillQuery {
+    /// Send SIGKILL instead of SIGTERM.
+    #[serde(default)]
+    force: bool,
+}
+
+pub async fn kill_process(
+    State(state): State<AppState>,
+    Path(pid): Path<u32>,
+    Query(query): Query<KillQuery>,
+) -> Result<StatusCode, ApiError> {
+    state.processes.kill(pid, query.force).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use axum::extract::ws::{Message, WebSocket};
+use axum::extract::{State, WebSocketUpgrade};
+use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+use system_capabilities::Terminal;
+use system_capabilities::terminal::{TerminalEvent, TerminalSize};
+use tokio::sync::mpsc;
+
+use super::error::ApiError;
+use crate::state::AppState;
+
+const EVENT_CAPACITY: usize = 256;
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum ClientMessage {
+    #[serde(rename = "terminal_open")]
+    Open {
+        cwd: Option<PathBuf>,
+        #[serde(flatten)]
+        size: Option<TerminalSize>,
+    },
+    #[serde(rename = "terminal_input")]
+    Input { id: u64, data: String },
+    #[serde(rename = "terminal_resize")]
+    Resize {
+        id: u64,
+        #[serde(flatten)]
+        size: TerminalSize,
+    },
+    #[serde(rename = "terminal_close")]
+    Close { id: u64 },
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    TerminalOpened {
+        id: u64,
+        cwd: PathBuf,
+    },
+    TerminalClosed {
+        id: u64,
+    },
+    Error {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<u64>,
+        message: String,
+    },
+    ServerShutdown,
+    #[serde(untagged)]
+    Event(TerminalEvent),
+}
+
+/// WebSocket carrying interactive shells. A socket may open several
+/// terminals; all of them are hung up when it disconnects. Refused when no
+/// auth clients are configured and in read-only mode, here as well as by
+/// the scope and read-only layers, since a shell must never be open to
+/// anonymous callers or leak into a demo.
+pub async fn terminal(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
+    if !state.auth.is_enabled() {
+        return ApiError::Forbidden("the terminal requires a configured auth client".into())
+            .into_response();
+    }
+    if state.config.read_only {
+        return ApiError::Forbidden("server is running in read-only mode".into()).into_response();
+    }
+    ws.on_upgrade(move |socket| handle_socket(socket, state))
+}
+
+async fn handle_socket(mut socket: WebSocket, state: AppState) {
+    let (events_tx, mut events) = mpsc::channel(EVENT_CAPACITY);
+    let mut terminals = HashMap::new();
+
+    loop {
+        let reply = tokio::select! {
+            _ = state.shutdown.cancelled() => {
+                let _ = send(&mut socket, &ServerMessage::ServerShutdown).await;
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    match handle_message(&state, &mut terminals, &events_tx, &text).await {
+                        Some(reply) => reply,
+                        None => continue,
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            Some(event) = events.recv() => {
+                if let TerminalEvent::TerminalExit { id, .. } = &event {
+                    terminals.remove(id);
+                }
+                ServerMessage::Event(event)
+            }
+        };
+
+        if send(&mut socket, &reply).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Handles one client message. Input produces no reply unless it fails.
+async fn handle_message(
+    state: &AppState,
+    terminals: &mut HashMap<u64, Terminal>,
+    events: &mpsc::Sender<TerminalEvent>,
+    text: &str,
+) -> Option<ServerMessage> {
+    let message = match serde_json::from_str::<ClientMessage>(text) {
+        Ok(message) => message,
+        Err(err) => return Some(error(None, format!("invalid message: {err}"))),
+    };
+
+    match message {
+        ClientMessage::Open { cwd, size } => {
+            let cwd = match cwd.or_else(|| state.fs.allowed_roots().first().cloned()) {
+                Some(cwd) => cwd,
+                None => return Some(error(None, "cwd is required".into())),
+            };
+            let cwd = match state.fs.resolve(&cwd).await {
+                Ok(cwd) => cwd,
+                Err(err) => return Some(error(None, err.to_string())),
+            };
+            match state
+                .terminals
+                .open(&cwd, size.unwrap_or_default(), events.clone())
+            {
+                Ok(terminal) => {
+                    let id = terminal.id();
+                    terminals.insert(id, terminal);
+                    Some(ServerMessage::TerminalOpened { id, cwd })
+                }
+                Err(err) => Some(error(None, err.to_string())),
+            }
+        }
+        ClientMessage::Input { id, data } => {
+            let result = match terminals.get(&id) {
+                Some(terminal) => terminal.write(data.as_bytes()),
+                None => return Some(unknown(id)),
+            };
+            result.err().map(|err| error(Some(id), err.to_string()))
+        }
+        ClientMessage::Resize { id, size } => {
+            let result = match terminals.get(&id) {
+                Some(terminal) => terminal.resize(size),
+                None => return Some(unknown(id)),
+            };
+            result.err().map(|err| error(Some(id), err.to_string()))
+        }
+        ClientMessage::Close { id } => match terminals.remove(&id) {
+            Some(_) => Some(ServerMessage::TerminalClosed { id }),
+            None => Some(unknown(id)),
+        },
+    }
+}
+
+fn error(id: Option<u64>, message: String) -> ServerMessage {
+    ServerMessage::Error { id, message }
+}
+
+fn unknown(id: u64) -> ServerMessage {
+    error(Some(id), format!("unknown terminal: {id}"))
+}
+
+async fn send(socket: &mut WebSocket, message: &ServerMessage) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).unwrap_or_default();
+    socket.send(Message::Text(text.into())).await
+}
@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+
+use axum::Json;
+use axum::body::Body;
+use axum::extract::multipart::MultipartError;
+use axum::extract::{Multipart, Query, State};
+use axum::http::StatusCode;
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_TYPE};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use system_capabilities::fs::FileInfo;
+use tokio_util::io::ReaderStream;
+
+use super::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct UploadQuery {
+    /// Directory the uploaded files are written into.
+    path: PathBuf,
+    #[serde(default)]
+    overwrite: bool,
+}
+
+/// Accepts a multipart form and streams every file field to disk under
+/// `path`, named after the field's file name.
+pub async fn upload(
+    State(state): State<AppState>,
+    Query(query): Query<UploadQuery>,
+    mut multipart: Multipart,
+) -> Result<Json<Vec<FileInfo>>, ApiError> {
+    let mut uploaded = Vec::new();
+
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        let Some(file_name) = field.file_name().map(str::to_string) else {
+            continue;
+        };
+
+        let mut upload = state
+            .fs
+            .begin_upload(&query.path, &file_name, query.overwrite)
+            .await?;
+        while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+            upload.write_chunk(&chunk).await?;
+        }
+
+        let info = upload.finish().await?;
+        tracing::info!(path = %info.path.display(), size = info.size, "file uploaded");
+        uploaded.push(info);
+    }
+
+    if uploaded.is_empty() {
+        return Err(ApiError::BadRequest("no files in upload".into()));
+    }
+    Ok(Json(uploaded))
+}
+
+#[derive(Deserialize)]
+pub struct DownloadQuery {
+    path: PathBuf,
+}
+
+/// Streams a file as an attachment.
+pub async fn download(
+    State(state): State<AppState>,
+    Query(query): Query<DownloadQuery>,
+) -> Result<Response, ApiError> {
+    let (info, file) = state.fs.open_for_download(&query.path).await?;
+    let content_type = info
+        .mime_type
+        .clone()
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    let headers = [
+        (CONTENT_TYPE, content_type),
+        (CONTENT_LENGTH, info.size.to_string()),
+        (CONTENT_DISPOSITION, content_disposition(&info.name)),
+    ];
+    Ok((headers, Body::from_stream(ReaderStream::new(file))).into_response())
+}
+
+fn multipart_error(err: MultipartError) -> ApiError {
+    if err.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        return ApiError::PayloadTooLarge(err.body_text());
+    }
+    ApiError::BadRequest(format!("invalid multipart body: {}", err.body_text()))
+}
+
+/// `attachment` disposition with an ASCII fallback name and the exact name
+/// percent-encoded per RFC 6266.
+fn content_disposition(name: &str) -> String {
+    let fallback: String = name
+        .chars()
+        .map(|c| match c {
+            ' '..='~' if c != '"' && c != '\\' => c,
+            _ => '_',
+        })
+        .collect();
+
+    let mut encoded = String::new();
+    for byte in name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    format!("attachment; filename=\"{fallback}\"; filename*=UTF-8''{encoded}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_disposition_passes_plain_names_through() {
+        assert_eq!(
+            content_disposition("report-1.txt"),
+            "attachment; filename=\"report-1.txt\"; filename*=UTF-8''report-1.txt"
+        );
+    }
+
+    #[test]
+    fn content_disposition_escapes_quotes_and_non_ascii() {
+        assert_eq!(
+            content_disposition("a \"b\"\\é.txt"),
+            "attachment; filename=\"a _b___.txt\"; filename*=UTF-8''a%20%22b%22%5C%C3%A9.txt"
+        );
+    }
+
+    #[test]
+    fn content_disposition_cannot_inject_header_parameters() {
+        let value = content_disposition("x\"; filename=evil.sh\r\n");
+        assert!(!value.contains('\r') && !value.contains('\n'));
+        assert_eq!(value.matches('"').count(), 2);
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use axum::extract::ws::{Message, WebSocket};
+use axum::extract::{State, WebSocketUpgrade};
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use system_capabilities::FileSystemChanged;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Watch { path: PathBuf },
+    Unwatch { path: PathBuf },
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Watching { path: PathBuf },
+    Unwatched { path: PathBuf },
+    FileSystemChanged(FileSystemChanged),
+    Error { message: String },
+    ServerShutdown,
+}
+
+/// WebSocket on which clients `watch`/`unwatch` directories and receive
+/// `file_system_changed` notifications for them.
+pub async fn watch(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
+    ws.on_upgrade(move |socket| handle_socket(socket, state))
+}
+
+async fn handle_socket(mut socket: WebSocket, state: AppState) {
+    let mut changes = state.watcher.subscribe();
+    let mut watched = HashSet::new();
+
+    loop {
+        let reply = tokio::select! {
+            _ = state.shutdown.cancelled() => {
+                let _ = send(&mut socket, &ServerMessage::ServerShutdown).await;
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    handle_message(&state, &mut watched, &text).await
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            change = changes.recv() => match change {
+                Ok(change) if watched.iter().any(|path| change.path.starts_with(path)) => {
+                    ServerMessage::FileSystemChanged(change)
+                }
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("file watch client lagged, {skipped} changes dropped");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+        };
+
+        if send(&mut socket, &reply).await.is_err() {
+            break;
+        }
+    }
+
+    for path in &watched {
+        state.watcher.unwatch(path);
+    }
+}
+
+async fn handle_message(
+    state: &AppState,
+    watched: &mut HashSet<PathBuf>,
+    text: &str,
+) -> ServerMessage {
+    let message = match serde_json::from_str::<ClientMessage>(text) {
+        Ok(message) => message,
+        Err(err) => {
+            return ServerMessage::Error {
+                message: format!("invalid message: {err}"),
+            };
+        }
+    };
+
+    match message {
+        ClientMessage::Watch { path } => {
+            let path = match state.fs.resolve(&path).await {
+                Ok(path) => path,
+                Err(err) => {
+                    return ServerMessage::Error {
+                        message: err.to_string(),
+                    };
+                }
+            };
+            if !watched.contains(&path) {
+                if let Err(err) = state.watcher.watch(&path) {
+                    return ServerMessage::Error {
+                        message: err.to_string(),
+                    };
+                }
+                watched.insert(path.clone());
+            }
+            ServerMessage::Watching { path }
+        }
+        ClientMessage::Unwatch { path } => {
+            let path = state.fs.resolve(&path).await.unwrap_or(path);
+            if watched.remove(&path) {
+                state.watcher.unwatch(&path);
+            }
+            ServerMessage::Unwatched { path }
+        }
+    }
+}
+
+async fn send(socket: &mut WebSocket, message: &ServerMessage) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).unwrap_or_default();
+    socket.send(Message::Text(text.into())).await
+}
@@ -0,0 +1,106 @@
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use serde::Deserialize;
+
+use super::error::ApiError;
+use crate::state::AppState;
+use crate::workspace::{Workspace, WorkspaceSpec};
+
+const DEFAULT_RECENT_LIMIT: usize = 10;
+const MAX_RECENT_LIMIT: usize = 100;
+
+pub async fn list(State(state): State<AppState>) -> Result<Json<Vec<Workspace>>, ApiError> {
+    Ok(Json(state.workspaces.list().await?))
+}
+
+#[derive(Deserialize)]
+pub struct RecentQuery {
+    limit: Option<usize>,
+}
+
+/// Recently opened workspaces, most recent first.
+pub async fn recent(
+    State(state): State<AppState>,
+    Query(query): Query<RecentQuery>,
+) -> Result<Json<Vec<Workspace>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_RECENT_LIMIT)
+        .min(MAX_RECENT_LIMIT);
+    Ok(Json(state.workspaces.recent(limit).await?))
+}
+
+pub async fn get(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<Json<Workspace>, ApiError> {
+    Ok(Json(state.workspaces.get(id).await?))
+}
+
+pub async fn create(
+    State(state): State<AppState>,
+    Json(spec): Json<WorkspaceSpec>,
+) -> Result<Json<Workspace>, ApiError> {
+    let spec = validate(&state, spec).await?;
+    let workspace = state.workspaces.create(spec).await?;
+    tracing::info!(id = workspace.id, path = %workspace.path.display(), "workspace registered");
+    Ok(Json(workspace))
+}
+
+pub async fn update(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+    Json(spec): Json<WorkspaceSpec>,
+) -> Result<Json<Workspace>, ApiError> {
+    let spec = validate(&state, spec).await?;
+    Ok(Json(state.workspaces.update(id, spec).await?))
+}
+
+/// Marks the workspace as opened so it leads the recent list.
+pub async fn open(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<Json<Workspace>, ApiError> {
+    Ok(Json(state.workspaces.touch(id).await?))
+}
+
+/// Forgets the workspace; the directory itself is left alone.
+pub async fn delete(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<StatusCode, ApiError> {
+    state.workspaces.delete(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Trims the name and resolves the path, which must be a directory under
+/// the allowed roots.
+async fn validate(state: &AppState, spec: WorkspaceSpec) -> Result<WorkspaceSpec, ApiError> {
+    let name = spec.name.trim().to_string();
+    if name.is_empty() {
+        return Err(ApiError::BadRequest(
+            "workspace name must not be empty".into(),
+        ));
+    }
+    let path = state.fs.resolve(&spec.path).await?;
+    if !tokio::fs::metadata(&path)
+        .await
+        .map(|metadata| metadata.is_dir())
+        .unwrap_or(false)
+    {
+        return Err(ApiError::BadRequest(format!(
+            "not a directory: {}",
+            path.display()
+        )));
+    }
+    let default_agent = spec
+        .default_agent
+        .map(|agent| agent.trim().to_string())
+        .filter(|agent| !agent.is_empty());
+    Ok(WorkspaceSpec {
+        name,
+        path,
+        default_agent,
+    })
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::AuthConfig;
+
+/// Permission attached to an API token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Scope {
+    #[serde(rename = "fs:read")]
+    FsRead,
+    #[serde(rename = "fs:write")]
+    FsWrite,
+    /// Grants every other scope.
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl Scope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scope::FsRead => "fs:read",
+            Scope::FsWrite => "fs:write",
+            Scope::Admin => "admin",
+        }
+    }
+}
+
+/// The caller a request was authenticated as.
+#[derive(Debug, Clone)]
+pub struct Principal {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+}
+
+impl Principal {
+    /// Used for every request when no tokens are configured. Anonymous
+    /// callers may only read: writes, process control, the terminal and the
+    /// admin API need a configured token.
+    pub fn anonymous() -> Self {
+        Self {
+            name: "anonymous".to_string(),
+            scopes: vec![Scope::FsRead],
+        }
+    }
+
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes
+            .iter()
+            .any(|s| *s == scope || *s == Scope::Admin)
+    }
+}
+
+/// Resolves bearer tokens to principals.
+pub struct Authenticator {
+    clients: Vec<(String, Principal)>,
+}
+
+impl Authenticator {
+    pub fn new(config: &AuthConfig) -> Self {
+        let clients = config
+            .clients
+            .iter()
+            .map(|client| {
+                let principal = Principal {
+                    name: client.name.clone(),
+                    scopes: client.scopes.clone(),
+                };
+                (client.token.clone(), principal)
+            })
+            .collect();
+        Self { clients }
+    }
+
+    /// Authentication is only enforced once at least one client is configured.
+    pub fn is_enabled(&self) -> bool {
+        !self.clients.is_empty()
+    }
+
+    pub fn authenticate(&self, token: &str) -> Option<Principal> {
+        self.clients
+            .iter()
+            .find(|(expected, _)| constant_time_eq(expected.as_bytes(), token.as_bytes()))
+            .map(|(_, principal)| principal.clone())
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
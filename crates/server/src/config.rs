@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use system_capabilities::{FileSystemConfig, TerminalConfig};
+
+use crate::auth::Scope;
+
+/// Environment variable pointing at the server configuration file.
+pub const CONFIG_ENV: &str = "JISI_CONFIG";
+
+/// Configuration file looked up in the working directory when
+/// [`CONFIG_ENV`] is not set.
+pub const DEFAULT_CONFIG_FILE: &str = "jisi.toml";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("failed to read config file {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to parse config file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+/// Top-level server configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    pub host: IpAddr,
+    pub port: u16,
+    /// Public demo mode: serves the UI-facing capabilities route and
+    /// refuses every other API call, reads included, so the demo exposes
+    /// neither the filesystem nor the host's compute.
+    pub read_only: bool,
+    /// How long in-flight requests and open connections may take to finish
+    /// after a shutdown signal before the server exits anyway.
+    pub shutdown_timeout_secs: u64,
+    /// Directory for state the server persists itself, such as the
+    /// workspace registry. Relative paths resolve against the working
+    /// directory.
+    pub data_dir: PathBuf,
+    /// Default state of each feature flag, keyed by flag name.
+    pub features: BTreeMap<String, bool>,
+    pub auth: AuthConfig,
+    pub fs: FileSystemConfig,
+    pub terminal: TerminalConfig,
+}
+
+/// API authentication. While `clients` is empty requests are not
+/// authenticated and every caller only gets the `fs:read` scope.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    pub clients: Vec<AuthClient>,
+    /// Web origins, such as `http://localhost:5173`, allowed to send
+    /// mutating requests and open WebSockets in addition to the server's
+    /// own origin.
+    pub allowed_origins: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthClient {
+    pub name: String,
+    pub token: String,
+    pub scopes: Vec<Scope>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port: 3000,
+            read_only: false,
+            shutdown_timeout_secs: 10,
+            data_dir: PathBuf::from(".jisi"),
+            features: BTreeMap::new(),
+            auth: AuthConfig::default(),
+            fs: FileSystemConfig::default(),
+            terminal: TerminalConfig::default(),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Loads the configuration from [`CONFIG_ENV`], falling back to
+    /// [`DEFAULT_CONFIG_FILE`] and then to the built-in defaults.
+    pub fn load() -> Result<Self, ConfigError> {
+        if let Some(path) = std::env::var_os(CONFIG_ENV) {
+            return Self::from_file(Path::new(&path));
+        }
+
+        let default_path = Path::new(DEFAULT_CONFIG_FILE);
+        if default_path.is_file() {
+            return Self::from_file(default_path);
+        }
+
+        Ok(Self::default())
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        toml::from_str(&contents).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn bind_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.host, self.port)
+    }
+
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(self.shutdown_timeout_secs)
+    }
+
+    /// Returns the effective configuration with secrets masked, suitable for
+    /// logging and for the admin API.
+    pub fn masked(&self) -> Value {
+        let mut value = serde_json::to_value(self).unwrap_or(Value::Null);
+        mask_secrets(&mut value, false);
+        value
+    }
+}
+
+const SECRET_MARKERS: &[&str] = &["secret", "token", "password", "api_key", "apikey"];
+const MASK: &str = "********";
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_MARKERS.iter().any(|marker| key.contains(marker))
+}
+
+/// Replaces every string under a secret-looking key with [`MASK`]. Values of
+/// `env` maps are always masked since they routinely carry credentials.
+fn mask_secrets(value: &mut Value, masked: bool) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                let masked = masked || key == "env" || is_secret_key(key);
+                mask_secrets(child, masked);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                mask_secrets(item, masked);
+            }
+        }
+        Value::String(s) if masked && !s.is_empty() => *s = MASK.to_string(),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::http::header::AUTHORIZATION;
+    use serde_json::json;
+
+    use super::*;
+    use crate::testing::{app, request};
+
+    #[test]
+    fn mask_secrets_hides_secret_keys_and_env_values() {
+        let mut value = json!({
+            "name": "agent",
+            "api_key": "sk-1",
+            "nested": { "clientSecret": "s", "Password": "p", "empty_token": "" },
+            "env": { "PATH": "/usr/bin", "list": ["a", 1] },
+            "tokens": ["t1", "t2"],
+        });
+        mask_secrets(&mut value, false);
+        assert_eq!(
+            value,
+            json!({
+                "name": "agent",
+                "api_key": MASK,
+                "nested": { "clientSecret": MASK, "Password": MASK, "empty_token": "" },
+                "env": { "PATH": MASK, "list": [MASK, 1] },
+                "tokens": [MASK, MASK],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn admin_config_masks_client_tokens() {
+        let app = app(|config| {
+            config.auth.clients = vec![AuthClient {
+                name: "admin".into(),
+                token: "super-secret-token".into(),
+                scopes: vec![Scope::Admin],
+            }];
+        });
+        let request = request("GET", "/api/admin/config")
+            .header(AUTHORIZATION, "Bearer super-secret-token")
+            .body(Body::empty())
+            .unwrap();
+
+        let (status, body) = app.send(request).await;
+        assert_eq!(status, StatusCode::OK);
+        let client = &body["auth"]["clients"][0];
+        assert_eq!(client["name"], "admin");
+        assert_eq!(client["token"], MASK);
+        assert!(!body.to_string().contains("super-secret-token"));
+    }
+}
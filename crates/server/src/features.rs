@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use serde::Serialize;
+
+/// Feature flags declared in the server config, with runtime overrides set
+/// through the admin API. Overrides live in memory and reset on restart.
+pub struct FeatureFlags {
+    defaults: BTreeMap<String, bool>,
+    overrides: RwLock<BTreeMap<String, bool>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureFlag {
+    pub name: String,
+    pub enabled: bool,
+    pub default: bool,
+    pub overridden: bool,
+}
+
+impl FeatureFlags {
+    pub fn new(defaults: BTreeMap<String, bool>) -> Self {
+        Self {
+            defaults,
+            overrides: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Returns whether `name` is enabled. Undeclared flags are disabled.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        if let Some(enabled) = self.overrides.read().unwrap().get(name) {
+            return *enabled;
+        }
+        self.defaults.get(name).copied().unwrap_or(false)
+    }
+
+    pub fn is_declared(&self, name: &str) -> bool {
+        self.defaults.contains_key(name)
+    }
+
+    pub fn list(&self) -> Vec<FeatureFlag> {
+        let overrides = self.overrides.read().unwrap();
+        self.defaults
+            .iter()
+            .map(|(name, default)| {
+                let over = overrides.get(name).copied();
+                FeatureFlag {
+                    name: name.clone(),
+                    enabled: over.unwrap_or(*default),
+                    default: *default,
+                    overridden: over.is_some(),
+                }
+            })
+            .collect()
+    }
+
+    /// Names of all currently enabled flags.
+    pub fn enabled(&self) -> Vec<String> {
+        self.list()
+            .into_iter()
+            .filter(|flag| flag.enabled)
+            .map(|flag| flag.name)
+            .collect()
+    }
+
+    pub fn set_override(&self, name: &str, enabled: bool) {
+        self.overrides
+            .write()
+            .unwrap()
+            .insert(name.to_string(), enabled);
+    }
+
+    pub fn clear_override(&self, name: &str) {
+        self.overrides.write().unwrap().remove(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::http::header::{AUTHORIZATION, CONTENT_TYPE};
+
+    use super::*;
+    use crate::auth::Scope;
+    use crate::config::AuthClient;
+    use crate::testing::{app, request};
+
+    fn flags() -> FeatureFlags {
+        FeatureFlags::new(BTreeMap::from([
+            ("beta".to_string(), false),
+            ("stable".to_string(), true),
+        ]))
+    }
+
+    #[test]
+    fn defaults_apply_until_overridden() {
+        let flags = flags();
+        assert!(!flags.is_enabled("beta"));
+        assert!(flags.is_enabled("stable"));
+        assert!(!flags.is_enabled("undeclared"));
+        assert_eq!(flags.enabled(), ["stable"]);
+
+        flags.set_override("beta", true);
+        flags.set_override("stable", false);
+        assert_eq!(flags.enabled(), ["beta"]);
+        let beta = &flags.list()[0];
+        assert!(beta.enabled && !beta.default && beta.overridden);
+
+        flags.clear_override("beta");
+        assert!(!flags.is_enabled("beta"));
+        assert!(!flags.list()[0].overridden);
+    }
+
+    #[tokio::test]
+    async fn admin_api_overrides_declared_flags_only() {
+        let app = app(|config| {
+            config.features = BTreeMap::from([("beta".to_string(), false)]);
+            config.auth.clients = vec![AuthClient {
+                name: "admin".into(),
+                token: "admin-token".into(),
+                scopes: vec![Scope::Admin],
+            }];
+        });
+        let set = |name: &str| {
+            request("PUT", &format!("/api/admin/features/{name}"))
+                .header(AUTHORIZATION, "Bearer admin-token")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"enabled":true}"#))
+                .unwrap()
+        };
+
+        let (status, body) = app.send(set("beta")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body[0]["enabled"], true);
+        assert_eq!(app.send(set("undeclared")).await.0, StatusCode::NOT_FOUND);
+
+        let request = request("GET", "/api/capabilities")
+            .header(AUTHORIZATION, "Bearer admin-token")
+            .body(Body::empty())
+            .unwrap();
+        let (_, body) = app.send(request).await;
+        assert_eq!(body["features"][0], "beta");
+    }
+}
@@ -0,0 +1,85 @@
+//! Library facade for the jisi-code server.
+//!
+//! The binary in `main.rs` is a thin wrapper around [`build_app`]; embedders
+//! such as a desktop shell can call it directly, merge their own routes into
+//! the returned [`Router`] and serve it in-process.
+
+use axum::Router;
+
+pub mod api;
+pub mod auth;
+pub mod config;
+pub mod features;
+pub mod shutdown;
+pub mod state;
+pub mod workspace;
+
+#[cfg(test)]
+mod testing;
+
+pub use config::{ConfigError, ServerConfig};
+pub use state::AppState;
+
+/// Builds the application state from `config` and returns the fully wired
+/// router.
+pub fn build_app(config: ServerConfig) -> Router {
+    let state = AppState::new(config);
+    build_router(state)
+}
+
+/// Builds the router around an already constructed [`AppState`].
+pub fn build_router(state: AppState) -> Router {
+    Router::new()
+        .nest("/api", api::router(state.clone()))
+        .with_state(state)
+}
+
+/// Binds the configured address and serves [`build_app`] until a shutdown
+/// signal arrives. Shutdown stops accepting connections, cancels
+/// [`AppState::shutdown`] so long-lived connections can close, and waits up
+/// to the configured drain timeout for in-flight requests.
+pub async fn serve(config: ServerConfig) -> std::io::Result<()> {
+    tracing::info!(config = %config.masked(), "effective configuration");
+    if config.auth.clients.is_empty() {
+        tracing::warn!(
+            "no auth clients configured, anyone who can reach the API may read files; \
+             configure a client to enable writes, the terminal and admin routes"
+        );
+    }
+
+    let addr = config.bind_addr();
+    let drain_timeout = config.shutdown_timeout();
+    let state = AppState::new(config);
+    let shutdown = state.shutdown.clone();
+    let app = build_router(state);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!("listening on {}", listener.local_addr()?);
+
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            shutdown::wait_for_signal().await;
+            tracing::info!("shutdown requested, draining connections");
+            shutdown.cancel();
+        }
+    });
+
+    let server = axum::serve(listener, app).with_graceful_shutdown({
+        let shutdown = shutdown.clone();
+        async move { shutdown.cancelled().await }
+    });
+
+    tokio::select! {
+        result = server => result?,
+        _ = async {
+            shutdown.cancelled().await;
+            tokio::time::sleep(drain_timeout).await;
+        } => {
+            tracing::warn!("drain timeout of {drain_timeout:?} elapsed, exiting with open connections");
+        }
+    }
+
+    tracing::info!("server stopped");
+    Ok(())
+}
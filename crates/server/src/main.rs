@@ -1,3 +1,14 @@
-fn main() {
-    println!("Hello, world!");
+use jisi_server::ServerConfig;
+use tracing_subscriber::EnvFilter;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()))
+        .init();
+
+    let config = ServerConfig::load()?;
+    jisi_server::serve(config).await?;
+
+    Ok(())
 }
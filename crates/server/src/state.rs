@@ -0,0 +1,48 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use system_capabilities::{FileSystemCapabilities, FileWatcher, ProcessMonitor, TerminalManager};
+use tokio_util::sync::CancellationToken;
+
+use crate::auth::Authenticator;
+use crate::config::ServerConfig;
+use crate::features::FeatureFlags;
+use crate::workspace::WorkspaceRegistry;
+
+/// Shared state handed to every request handler.
+#[derive(Clone)]
+pub struct AppState {
+    pub config: Arc<ServerConfig>,
+    pub features: Arc<FeatureFlags>,
+    pub auth: Arc<Authenticator>,
+    pub fs: Arc<FileSystemCapabilities>,
+    pub watcher: Arc<FileWatcher>,
+    pub processes: ProcessMonitor,
+    pub terminals: Arc<TerminalManager>,
+    pub workspaces: Arc<WorkspaceRegistry>,
+    /// Cancelled when the server starts shutting down. Long-lived
+    /// connections watch it to say goodbye to their clients.
+    pub shutdown: CancellationToken,
+}
+
+impl AppState {
+    pub fn new(config: ServerConfig) -> Self {
+        let features = FeatureFlags::new(config.features.clone());
+        let auth = Authenticator::new(&config.auth);
+        let fs = FileSystemCapabilities::new(config.fs.clone());
+        let workspaces = WorkspaceRegistry::new(&config.data_dir);
+        let terminals = TerminalManager::new(config.terminal.clone());
+        let watcher = FileWatcher::new(Duration::from_millis(config.fs.watch_debounce_ms));
+        Self {
+            config: Arc::new(config),
+            features: Arc::new(features),
+            auth: Arc::new(auth),
+            fs: Arc::new(fs),
+            watcher: Arc::new(watcher),
+            processes: ProcessMonitor::new(),
+            terminals: Arc::new(terminals),
+            workspaces: Arc::new(workspaces),
+            shutdown: CancellationToken::new(),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Helpers for driving the full router in unit tests.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use serde_json::Value;
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+use crate::config::ServerConfig;
+
+/// The router built from a config whose data directory and single allowed
+/// root live in a temporary directory.
+pub struct TestApp {
+    pub router: Router,
+    /// The allowed root, canonicalized.
+    pub root: std::path::PathBuf,
+    _dir: TempDir,
+}
+
+pub fn app(configure: impl FnOnce(&mut ServerConfig)) -> TestApp {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().canonicalize().unwrap();
+    let root = base.join("root");
+    std::fs::create_dir(&root).unwrap();
+
+    let mut config = ServerConfig {
+        data_dir: base.join("data"),
+        ..ServerConfig::default()
+    };
+    config.fs.allowed_roots = vec![root.clone()];
+    configure(&mut config);
+
+    TestApp {
+        router: crate::build_app(config),
+        root,
+        _dir: dir,
+    }
+}
+
+impl TestApp {
+    /// Sends `request` and returns the status with the body parsed as JSON,
+    /// or `Value::Null` when it is not JSON.
+    pub async fn send(&self, request: Request<Body>) -> (StatusCode, Value) {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    pub async fn get(&self, uri: &str) -> (StatusCode, Value) {
+        self.send(request("GET", uri).body(Body::empty()).unwrap())
+            .await
+    }
+}
+
+pub fn request(method: &str, uri: &str) -> axum::http::request::Builder {
+    Request::builder().method(method).uri(uri)
+}
@@ -0,0 +1,346 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+/// File under [`ServerConfig::data_dir`](crate::ServerConfig::data_dir)
+/// holding the registered workspaces.
+pub const WORKSPACES_FILE: &str = "workspaces.json";
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkspaceError {
+    #[error("workspace not found: {0}")]
+    NotFound(u64),
+    #[error("a workspace is already registered for {}", .0.display())]
+    DuplicatePath(PathBuf),
+    #[error("failed to read workspace registry {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to parse workspace registry {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("failed to write workspace registry {path}: {source}")]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// A project directory the user has registered for quick access.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    pub id: u64,
+    pub name: String,
+    pub path: PathBuf,
+    pub default_agent: Option<String>,
+    /// Seconds since the Unix epoch.
+    pub created_at: u64,
+    /// Seconds since the Unix epoch; `None` until first opened.
+    pub last_opened_at: Option<u64>,
+}
+
+/// Fields a client supplies when registering or updating a workspace.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkspaceSpec {
+    pub name: String,
+    pub path: PathBuf,
+    #[serde(default)]
+    pub default_agent: Option<String>,
+}
+
+/// Registered workspaces, persisted as JSON. The file is loaded on first
+/// use and rewritten after every change.
+pub struct WorkspaceRegistry {
+    path: PathBuf,
+    workspaces: Mutex<Option<Vec<Workspace>>>,
+}
+
+impl WorkspaceRegistry {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join(WORKSPACES_FILE),
+            workspaces: Mutex::new(None),
+        }
+    }
+
+    /// All workspaces ordered by name.
+    pub async fn list(&self) -> Result<Vec<Workspace>, WorkspaceError> {
+        let mut guard = self.workspaces.lock().await;
+        let mut workspaces = self.loaded(&mut guard).await?.clone();
+        workspaces.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        Ok(workspaces)
+    }
+
+    /// Workspaces that have been opened, most recent first.
+    pub async fn recent(&self, limit: usize) -> Result<Vec<Workspace>, WorkspaceError> {
+        let mut guard = self.workspaces.lock().await;
+        let mut workspaces: Vec<_> = self
+            .loaded(&mut guard)
+            .await?
+            .iter()
+            .filter(|workspace| workspace.last_opened_at.is_some())
+            .cloned()
+            .collect();
+        workspaces.sort_by_key(|workspace| std::cmp::Reverse(workspace.last_opened_at));
+        workspaces.truncate(limit);
+        Ok(workspaces)
+    }
+
+    pub async fn get(&self, id: u64) -> Result<Workspace, WorkspaceError> {
+        let mut guard = self.workspaces.lock().await;
+        find(self.loaded(&mut guard).await?, id).cloned()
+    }
+
+    /// Registers a workspace. `spec.path` must already be resolved against
+    /// the allowed roots.
+    pub async fn create(&self, spec: WorkspaceSpec) -> Result<Workspace, WorkspaceError> {
+        let mut guard = self.workspaces.lock().await;
+        let workspaces = self.loaded(&mut guard).await?;
+        ensure_unique_path(workspaces, &spec.path, None)?;
+
+        let workspace = Workspace {
+            id: workspaces.iter().map(|w| w.id).max().unwrap_or(0) + 1,
+            name: spec.name,
+            path: spec.path,
+            default_agent: spec.default_agent,
+            created_at: now(),
+            last_opened_at: None,
+        };
+        workspaces.push(workspace.clone());
+        self.save(workspaces).await?;
+        Ok(workspace)
+    }
+
+    /// Replaces a workspace's name, path and default agent.
+    pub async fn update(&self, id: u64, spec: WorkspaceSpec) -> Result<Workspace, WorkspaceError> {
+        let mut guard = self.workspaces.lock().await;
+        let workspaces = self.loaded(&mut guard).await?;
+        ensure_unique_path(workspaces, &spec.path, Some(id))?;
+
+        let workspace = find(workspaces, id)?;
+        workspace.name = spec.name;
+        workspace.path = spec.path;
+        workspace.default_agent = spec.default_agent;
+        let workspace = workspace.clone();
+        self.save(workspaces).await?;
+        Ok(workspace)
+    }
+
+    /// Records that the workspace was opened, moving it to the top of the
+    /// recent list.
+    pub async fn touch(&self, id: u64) -> Result<Workspace, WorkspaceError> {
+        let mut guard = self.workspaces.lock().await;
+        let workspaces = self.loaded(&mut guard).await?;
+        let workspace = find(workspaces, id)?;
+        workspace.last_opened_at = Some(now());
+        let workspace = workspace.clone();
+        self.save(workspaces).await?;
+        Ok(workspace)
+    }
+
+    pub async fn delete(&self, id: u64) -> Result<(), WorkspaceError> {
+        let mut guard = self.workspaces.lock().await;
+        let workspaces = self.loaded(&mut guard).await?;
+        let index = workspaces
+            .iter()
+            .position(|workspace| workspace.id == id)
+            .ok_or(WorkspaceError::NotFound(id))?;
+        workspaces.remove(index);
+        self.save(workspaces).await
+    }
+
+    async fn loaded<'a>(
+        &self,
+        guard: &'a mut Option<Vec<Workspace>>,
+    ) -> Result<&'a mut Vec<Workspace>, WorkspaceError> {
+        if guard.is_none() {
+            *guard = Some(self.load().await?);
+        }
+        Ok(guard.as_mut().unwrap())
+    }
+
+    async fn load(&self) -> Result<Vec<Workspace>, WorkspaceError> {
+        let contents = match tokio::fs::read(&self.path).await {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => {
+                return Err(WorkspaceError::Read {
+                    path: self.path.clone(),
+                    source,
+                });
+            }
+        };
+        serde_json::from_slice(&contents).map_err(|source| WorkspaceError::Parse {
+            path: self.path.clone(),
+            source,
+        })
+    }
+
+    /// Writes through a temporary file so a crash never leaves a truncated
+    /// registry behind.
+    async fn save(&self, workspaces: &[Workspace]) -> Result<(), WorkspaceError> {
+        let write_error = |source| WorkspaceError::Write {
+            path: self.path.clone(),
+            source,
+        };
+        let contents = serde_json::to_vec_pretty(workspaces).map_err(std::io::Error::other);
+        let contents = contents.map_err(write_error)?;
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(write_error)?;
+        }
+        let temp = self.path.with_extension("json.tmp");
+        tokio::fs::write(&temp, contents)
+            .await
+            .map_err(write_error)?;
+        tokio::fs::rename(&temp, &self.path)
+            .await
+            .map_err(write_error)
+    }
+}
+
+fn find(workspaces: &mut [Workspace], id: u64) -> Result<&mut Workspace, WorkspaceError> {
+    workspaces
+        .iter_mut()
+        .find(|workspace| workspace.id == id)
+        .ok_or(WorkspaceError::NotFound(id))
+}
+
+fn ensure_unique_path(
+    workspaces: &[Workspace],
+    path: &Path,
+    except: Option<u64>,
+) -> Result<(), WorkspaceError> {
+    let taken = workspaces
+        .iter()
+        .any(|workspace| workspace.path == path && Some(workspace.id) != except);
+    if taken {
+        Err(WorkspaceError::DuplicatePath(path.to_path_buf()))
+    } else {
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(name: &str, path: &str) -> WorkspaceSpec {
+        WorkspaceSpec {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+            default_agent: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn registry_round_trips_through_its_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path().join("data");
+        let registry = WorkspaceRegistry::new(&data_dir);
+        assert!(registry.list().await.unwrap().is_empty());
+
+        let b = registry.create(spec("b", "/work/b")).await.unwrap();
+        let a = registry.create(spec("a", "/work/a")).await.unwrap();
+        assert_eq!((b.id, a.id), (1, 2));
+        assert!(data_dir.join(WORKSPACES_FILE).is_file());
+
+        let reloaded = WorkspaceRegistry::new(&data_dir);
+        let names: Vec<_> = reloaded
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|w| w.name)
+            .collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(
+            reloaded.get(1).await.unwrap().path,
+            PathBuf::from("/work/b")
+        );
+    }
+
+    #[tokio::test]
+    async fn paths_are_unique_across_create_and_update() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = WorkspaceRegistry::new(dir.path());
+        registry.create(spec("a", "/work/a")).await.unwrap();
+        let b = registry.create(spec("b", "/work/b")).await.unwrap();
+
+        let err = registry.create(spec("again", "/work/a")).await.unwrap_err();
+        assert!(matches!(err, WorkspaceError::DuplicatePath(_)));
+        let err = registry
+            .update(b.id, spec("b", "/work/a"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WorkspaceError::DuplicatePath(_)));
+
+        // Keeping its own path is not a conflict.
+        let renamed = registry
+            .update(b.id, spec("renamed", "/work/b"))
+            .await
+            .unwrap();
+        assert_eq!(renamed.name, "renamed");
+    }
+
+    #[tokio::test]
+    async fn recent_lists_opened_workspaces_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = WorkspaceRegistry::new(dir.path());
+        let a = registry.create(spec("a", "/work/a")).await.unwrap();
+        registry.create(spec("b", "/work/b")).await.unwrap();
+        assert!(registry.recent(10).await.unwrap().is_empty());
+
+        let opened = registry.touch(a.id).await.unwrap();
+        assert!(opened.last_opened_at.is_some());
+        let recent = registry.recent(10).await.unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].id, a.id);
+        assert!(registry.recent(0).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_workspaces_are_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = WorkspaceRegistry::new(dir.path());
+        let a = registry.create(spec("a", "/work/a")).await.unwrap();
+        registry.delete(a.id).await.unwrap();
+
+        assert!(matches!(
+            registry.get(a.id).await,
+            Err(WorkspaceError::NotFound(1))
+        ));
+        assert!(matches!(
+            registry.touch(a.id).await,
+            Err(WorkspaceError::NotFound(1))
+        ));
+        assert!(matches!(
+            registry.delete(a.id).await,
+            Err(WorkspaceError::NotFound(1))
+        ));
+    }
+
+    #[tokio::test]
+    async fn a_corrupt_registry_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(WORKSPACES_FILE), "not json").unwrap();
+        let registry = WorkspaceRegistry::new(dir.path());
+        assert!(matches!(
+            registry.list().await,
+            Err(WorkspaceError::Parse { .. })
+        ));
+    }
+}
@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+pub type Result<T> = std::result::Result<T, CapabilityError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CapabilityError {
+    #[error("path not found: {}", .0.display())]
+    NotFound(PathBuf),
+    #[error("path is outside the allowed roots: {}", .0.display())]
+    OutsideAllowedRoots(PathBuf),
+    #[error("path already exists: {}", .0.display())]
+    AlreadyExists(PathBuf),
+    #[error("not a file: {}", .0.display())]
+    NotAFile(PathBuf),
+    #[error("refusing to modify an allowed root: {}", .0.display())]
+    ProtectedRoot(PathBuf),
+    #[error("not a git repository: {}", .0.display())]
+    NotARepository(PathBuf),
+    #[error("git: {0}")]
+    Git(String),
+    #[error("exceeds the size limit of {limit} bytes")]
+    TooLarge { limit: u64 },
+    #[error("process not found: {0}")]
+    ProcessNotFound(u32),
+    #[error("process {0} was not started by this server")]
+    ProcessNotOwned(u32),
+    #[error("terminal session limit of {limit} reached")]
+    TerminalLimit { limit: usize },
+    #[error("terminal: {0}")]
+    Terminal(String),
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,342 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::oneshot;
+
+use super::{FileSystemCapabilities, open_regular_blocking};
+use crate::error::{CapabilityError, Result};
+
+const READ_BUFFER_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Blake3,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileHash {
+    pub path: PathBuf,
+    pub algorithm: HashAlgorithm,
+    /// Lowercase hex digest.
+    pub hash: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DuplicateOptions {
+    pub algorithm: HashAlgorithm,
+    /// Files smaller than this are ignored; empty files are always ignored.
+    pub min_size: u64,
+    pub max_depth: Option<usize>,
+    /// Descend into and consider entries whose name starts with a dot.
+    pub include_hidden: bool,
+    /// Stop collecting candidates after this many files.
+    pub max_files: usize,
+    /// How long the walk and the hashing may take together; capped at the
+    /// configured search budget.
+    pub budget: Duration,
+}
+
+/// Files sharing the same content.
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    pub hash: String,
+    /// Size of each copy.
+    pub size: u64,
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateReport {
+    pub algorithm: HashAlgorithm,
+    /// Groups ordered by the space their extra copies take, largest first.
+    pub groups: Vec<DuplicateGroup>,
+    pub scanned: usize,
+    /// Files whose content was hashed; only files sharing a size with
+    /// another file are.
+    pub hashed: usize,
+    /// Whether the walk stopped early because `max_files` was reached.
+    pub truncated: bool,
+    /// Whether the time budget or the search entry limit ran out before the
+    /// walk and hashing finished, in which case groups may be missing.
+    pub incomplete: bool,
+    pub elapsed_ms: u64,
+}
+
+impl FileSystemCapabilities {
+    /// Streams the file at `path` through `algorithm` on a blocking thread.
+    pub async fn hash_file(&self, path: &Path, algorithm: HashAlgorithm) -> Result<FileHash> {
+        let path = self.resolve(path).await?;
+        let metadata = tokio::fs::metadata(&path).await?;
+        if !metadata.is_file() {
+            return Err(CapabilityError::NotAFile(path));
+        }
+        tokio::task::spawn_blocking(move || {
+            let (hash, size) = hash_blocking(&path, algorithm, &|| false)?
+                .expect("hashing without a stop condition always finishes");
+            Ok(FileHash {
+                path,
+                algorithm,
+                hash,
+                size,
+            })
+        })
+        .await
+        .map_err(|err| CapabilityError::Io(std::io::Error::other(err)))?
+    }
+
+    /// Groups the files under `base` by content. Files are first grouped by
+    /// size so only potential duplicates are read. Symlinks are skipped.
+    /// The walk counts towards the search entry limit, and both the walk and
+    /// the hashing stop once the budget runs out or the returned future is
+    /// dropped.
+    pub async fn find_duplicates(
+        &self,
+        base: &Path,
+        mut options: DuplicateOptions,
+    ) -> Result<DuplicateReport> {
+        let base = self.resolve(base).await?;
+        options.budget = options
+            .budget
+            .min(Duration::from_millis(self.config.max_search_budget_ms));
+        let max_entries = self.config.max_search_entries;
+        let (tx, rx) = oneshot::channel();
+        tokio::task::spawn_blocking(move || {
+            let report = find_duplicates_blocking(base, options, max_entries, &|| tx.is_closed());
+            let _ = tx.send(report);
+        });
+        rx.await
+            .map_err(|err| CapabilityError::Io(std::io::Error::other(err)))
+    }
+}
+
+/// `cancelled` reports whether the caller has gone away.
+fn find_duplicates_blocking(
+    base: PathBuf,
+    options: DuplicateOptions,
+    max_entries: usize,
+    cancelled: &dyn Fn() -> bool,
+) -> DuplicateReport {
+    let started = Instant::now();
+    let stop = || cancelled() || started.elapsed() >= options.budget;
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    let mut entries_seen = 0;
+    let mut scanned = 0;
+    let mut truncated = false;
+    let mut incomplete = false;
+
+    let mut queue = VecDeque::from([(base, 0usize)]);
+    'walk: while let Some((dir, depth)) = queue.pop_front() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entries_seen >= max_entries || stop() {
+                incomplete = true;
+                break 'walk;
+            }
+            entries_seen += 1;
+            if !options.include_hidden && entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                if options.max_depth.is_none_or(|max| depth < max) {
+                    queue.push_back((entry.path(), depth + 1));
+                }
+                continue;
+            }
+            if !metadata.is_file() {
+                continue;
+            }
+            if scanned == options.max_files {
+                truncated = true;
+                break 'walk;
+            }
+            scanned += 1;
+            let size = metadata.len();
+            if size > 0 && size >= options.min_size {
+                by_size.entry(size).or_default().push(entry.path());
+            }
+        }
+    }
+
+    let mut hashed = 0;
+    let mut groups = Vec::new();
+    'hash: for (size, paths) in by_size {
+        if paths.len() < 2 {
+            continue;
+        }
+        let mut by_hash: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+        for path in paths {
+            match hash_blocking(&path, options.algorithm, &stop) {
+                Ok(Some((hash, _))) => {
+                    hashed += 1;
+                    by_hash.entry(hash).or_default().push(path);
+                }
+                Ok(None) => {
+                    incomplete = true;
+                    break 'hash;
+                }
+                // Files that vanish or become unreadable mid-scan are skipped.
+                Err(_) => {}
+            }
+        }
+        groups.extend(
+            by_hash
+                .into_iter()
+                .filter(|(_, paths)| paths.len() > 1)
+                .map(|(hash, mut paths)| {
+                    paths.sort();
+                    DuplicateGroup { hash, size, paths }
+                }),
+        );
+    }
+    groups.sort_by(|a, b| {
+        let wasted = |group: &DuplicateGroup| group.size * (group.paths.len() as u64 - 1);
+        wasted(b)
+            .cmp(&wasted(a))
+            .then_with(|| a.paths.cmp(&b.paths))
+    });
+
+    DuplicateReport {
+        algorithm: options.algorithm,
+        groups,
+        scanned,
+        hashed,
+        truncated,
+        incomplete,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// Returns the hex digest of the file at `path` and the number of bytes
+/// read, or `None` when `stop` returned true before the end of the file.
+fn hash_blocking(
+    path: &Path,
+    algorithm: HashAlgorithm,
+    stop: &dyn Fn() -> bool,
+) -> Result<Option<(String, u64)>> {
+    let mut file = open_regular_blocking(path)?;
+    let mut buffer = vec![0; READ_BUFFER_BYTES];
+    let mut size = 0;
+    let mut hasher = Hasher::new(algorithm);
+    loop {
+        if stop() {
+            return Ok(None);
+        }
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        size += read as u64;
+        hasher.update(&buffer[..read]);
+    }
+    Ok(Some((hasher.finalize(), size)))
+}
+
+enum Hasher {
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::default()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(data),
+            Hasher::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    fn finalize(self) -> String {
+        match self {
+            Hasher::Sha256(hasher) => hex::encode(hasher.finalize()),
+            Hasher::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::fixture;
+    use super::*;
+    use crate::fs::FileSystemConfig;
+
+    fn options() -> DuplicateOptions {
+        DuplicateOptions {
+            algorithm: HashAlgorithm::Sha256,
+            min_size: 0,
+            max_depth: None,
+            include_hidden: false,
+            max_files: 100,
+            budget: Duration::from_secs(10),
+        }
+    }
+
+    #[tokio::test]
+    async fn find_duplicates_groups_identical_files() {
+        let f = fixture();
+        std::fs::create_dir(f.root.join("sub")).unwrap();
+        std::fs::write(f.root.join("a.txt"), "same").unwrap();
+        std::fs::write(f.root.join("sub/b.txt"), "same").unwrap();
+        std::fs::write(f.root.join("c.txt"), "diff").unwrap();
+        std::fs::write(f.root.join("d.txt"), "longer").unwrap();
+
+        let report = f.fs.find_duplicates(&f.root, options()).await.unwrap();
+        assert!(!report.truncated && !report.incomplete);
+        assert_eq!((report.scanned, report.hashed), (4, 3));
+        assert_eq!(report.groups.len(), 1);
+        let expected = vec![f.root.join("a.txt"), f.root.join("sub/b.txt")];
+        assert_eq!(report.groups[0].paths, expected);
+    }
+
+    #[tokio::test]
+    async fn find_duplicates_stops_when_the_budget_runs_out() {
+        let f = fixture();
+        std::fs::write(f.root.join("a.txt"), "same").unwrap();
+        std::fs::write(f.root.join("b.txt"), "same").unwrap();
+
+        let options = DuplicateOptions {
+            budget: Duration::ZERO,
+            ..options()
+        };
+        let report = f.fs.find_duplicates(&f.root, options).await.unwrap();
+        assert!(report.incomplete);
+        assert!(report.groups.is_empty());
+    }
+
+    #[tokio::test]
+    async fn find_duplicates_counts_directories_against_the_entry_limit() {
+        let f = fixture();
+        for i in 0..10 {
+            std::fs::create_dir(f.root.join(format!("dir{i}"))).unwrap();
+        }
+        let fs = FileSystemCapabilities::new(FileSystemConfig {
+            allowed_roots: vec![f.root.clone()],
+            max_search_entries: 5,
+            ..FileSystemConfig::default()
+        });
+
+        let report = fs.find_duplicates(&f.root, options()).await.unwrap();
+        assert!(report.incomplete);
+        assert_eq!(report.scanned, 0);
+    }
+}
@@ -0,0 +1,419 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CapabilityError, Result};
+
+mod hash;
+mod project;
+mod read;
+mod recent;
+mod search;
+mod size;
+mod sniff;
+mod transfer;
+mod trash;
+mod tree;
+mod write;
+
+pub use hash::{DuplicateGroup, DuplicateOptions, DuplicateReport, FileHash, HashAlgorithm};
+pub use project::ProjectInfo;
+pub use read::{FileContent, FileEncoding};
+pub use recent::RecentFiles;
+pub use search::{SearchEvent, SearchOptions, SearchSummary};
+pub use size::{DirectorySize, DirectorySizeEvent};
+pub use transfer::Upload;
+pub use trash::{TRASH_DIR, TrashEntry};
+pub use tree::FileSystemEntry;
+pub use write::{DeleteOutcome, WriteOptions};
+
+/// Filesystem access settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileSystemConfig {
+    /// Directories the API may touch. Empty means unrestricted.
+    pub allowed_roots: Vec<PathBuf>,
+    /// Upper bound on the bytes returned by a single read.
+    pub max_read_bytes: u64,
+    /// Upper bound on the size of a single file write.
+    pub max_write_bytes: u64,
+    /// Upper bound on the size of a single uploaded file.
+    pub max_upload_bytes: u64,
+    /// Window over which file watcher events are coalesced.
+    pub watch_debounce_ms: u64,
+    /// Longest a single directory size computation may run.
+    pub max_size_budget_ms: u64,
+    /// Longest a single search or recent files walk may run.
+    pub max_search_budget_ms: u64,
+    /// Upper bound on the entries a single search or recent files walk
+    /// examines.
+    pub max_search_entries: usize,
+}
+
+impl Default for FileSystemConfig {
+    fn default() -> Self {
+        Self {
+            allowed_roots: Vec::new(),
+            max_read_bytes: 10 * 1024 * 1024,
+            max_write_bytes: 10 * 1024 * 1024,
+            max_upload_bytes: 512 * 1024 * 1024,
+            watch_debounce_ms: 200,
+            max_size_budget_ms: 30_000,
+            max_search_budget_ms: 30_000,
+            max_search_entries: 1_000_000,
+        }
+    }
+}
+
+/// Filesystem operations confined to [`FileSystemConfig::allowed_roots`].
+pub struct FileSystemCapabilities {
+    config: FileSystemConfig,
+    roots: Vec<PathBuf>,
+}
+
+impl FileSystemCapabilities {
+    /// Roots that cannot be canonicalized (typically because they do not
+    /// exist) are kept as given, so they never match a canonical path.
+    pub fn new(config: FileSystemConfig) -> Self {
+        let roots = config
+            .allowed_roots
+            .iter()
+            .map(|root| std::fs::canonicalize(root).unwrap_or_else(|_| root.clone()))
+            .collect();
+        Self { config, roots }
+    }
+
+    pub fn config(&self) -> &FileSystemConfig {
+        &self.config
+    }
+
+    pub fn allowed_roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Canonicalizes an existing `path` and checks it against the allowed
+    /// roots.
+    pub async fn resolve(&self, path: &Path) -> Result<PathBuf> {
+        let canonical = tokio::fs::canonicalize(path)
+            .await
+            .map_err(|err| map_not_found(err, path))?;
+        self.check_allowed(&canonical)?;
+        Ok(canonical)
+    }
+
+    /// Resolves a path that may not exist yet: its parent must exist and be
+    /// inside the allowed roots.
+    pub async fn resolve_new(&self, path: &Path) -> Result<PathBuf> {
+        let name = match path.file_name() {
+            Some(name) if path.is_absolute() => name,
+            _ => {
+                return Err(CapabilityError::InvalidArgument(format!(
+                    "expected an absolute path to a file or directory: {}",
+                    path.display()
+                )));
+            }
+        };
+        let parent = path.parent().unwrap_or(Path::new("/"));
+        let parent = self.resolve(parent).await?;
+        Ok(parent.join(name))
+    }
+
+    /// Resolves an existing path that is about to be moved or removed. Only
+    /// the parent is canonicalized, so a symlink resolves to the link itself
+    /// rather than its target. Allowed roots themselves are protected.
+    async fn resolve_mutable(&self, path: &Path) -> Result<PathBuf> {
+        let resolved = match self.resolve_new(path).await {
+            Ok(resolved) => resolved,
+            // An allowed root's parent is usually outside the allowed roots;
+            // report the root as protected rather than out of bounds.
+            Err(err) => match tokio::fs::canonicalize(path).await {
+                Ok(canonical) if self.roots.contains(&canonical) => {
+                    return Err(CapabilityError::ProtectedRoot(canonical));
+                }
+                _ => return Err(err),
+            },
+        };
+        tokio::fs::symlink_metadata(&resolved)
+            .await
+            .map_err(|err| map_not_found(err, path))?;
+        if self.roots.contains(&resolved) || resolved.parent().is_none() {
+            return Err(CapabilityError::ProtectedRoot(resolved));
+        }
+        Ok(resolved)
+    }
+
+    /// The allowed root containing `canonical`, if any.
+    fn root_of(&self, canonical: &Path) -> Option<&Path> {
+        self.roots
+            .iter()
+            .filter(|root| canonical.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .map(PathBuf::as_path)
+    }
+
+    fn check_allowed(&self, canonical: &Path) -> Result<()> {
+        if self.roots.is_empty() || self.roots.iter().any(|root| canonical.starts_with(root)) {
+            Ok(())
+        } else {
+            Err(CapabilityError::OutsideAllowedRoots(
+                canonical.to_path_buf(),
+            ))
+        }
+    }
+}
+
+/// Metadata about a single file or directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileInfo {
+    pub path: PathBuf,
+    pub name: String,
+    pub is_dir: bool,
+    pub size: u64,
+    /// Last modification time in seconds since the Unix epoch.
+    pub modified: Option<u64>,
+    /// MIME type detected from the file's content; `None` for directories.
+    pub mime_type: Option<String>,
+    /// Whether the content looks binary rather than text; `None` for
+    /// directories.
+    pub is_binary: Option<bool>,
+}
+
+impl FileInfo {
+    /// Builds the info from metadata alone, without content detection.
+    pub fn from_metadata(path: PathBuf, metadata: &std::fs::Metadata) -> Self {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs());
+        Self {
+            path,
+            name,
+            is_dir: metadata.is_dir(),
+            size: metadata.len(),
+            modified,
+            mime_type: None,
+            is_binary: None,
+        }
+    }
+
+    /// Loads metadata and detects the content type of regular files.
+    pub async fn load(path: PathBuf) -> Result<Self> {
+        let metadata = tokio::fs::metadata(&path).await?;
+        let mut info = Self::from_metadata(path, &metadata);
+        if metadata.is_file() {
+            let head = sniff::read_head(&info.path).await?;
+            info.set_content_type(&head);
+        }
+        Ok(info)
+    }
+
+    /// Detects the content type of a file, reading its first bytes. For
+    /// use on blocking threads; unreadable files and anything other than a
+    /// regular file (or a link to one) are left undetected.
+    pub fn sniff_blocking(mut self) -> Self {
+        if !self.is_dir
+            && let Ok(head) = sniff::read_head_blocking(&self.path)
+        {
+            self.set_content_type(&head);
+        }
+        self
+    }
+
+    fn set_content_type(&mut self, head: &[u8]) {
+        let content = sniff::detect(&self.path, head);
+        self.mime_type = Some(content.mime_type);
+        self.is_binary = Some(content.is_binary);
+    }
+}
+
+/// Opens a regular file (or a link to one) for reading. Anything else is
+/// refused with `InvalidInput`: the open is non-blocking so a FIFO without
+/// a writer cannot hang the caller, and the type is checked on the opened
+/// handle so it cannot be swapped in between.
+pub(crate) async fn open_regular(path: &Path) -> std::io::Result<tokio::fs::File> {
+    let mut open = tokio::fs::OpenOptions::new();
+    open.read(true);
+    #[cfg(unix)]
+    open.custom_flags(libc::O_NONBLOCK);
+    let file = open.open(path).await?;
+    ensure_regular(&file.metadata().await?)?;
+    Ok(file)
+}
+
+pub(crate) fn open_regular_blocking(path: &Path) -> std::io::Result<std::fs::File> {
+    let mut open = std::fs::OpenOptions::new();
+    open.read(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::custom_flags(&mut open, libc::O_NONBLOCK);
+    let file = open.open(path)?;
+    ensure_regular(&file.metadata()?)?;
+    Ok(file)
+}
+
+fn ensure_regular(metadata: &std::fs::Metadata) -> std::io::Result<()> {
+    if metadata.is_file() {
+        Ok(())
+    } else {
+        Err(std::io::Error::new(
+            ErrorKind::InvalidInput,
+            "not a regular file",
+        ))
+    }
+}
+
+pub(crate) fn map_not_found(err: std::io::Error, path: &Path) -> CapabilityError {
+    if err.kind() == ErrorKind::NotFound {
+        CapabilityError::NotFound(path.to_path_buf())
+    } else {
+        CapabilityError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    pub(super) struct Fixture {
+        _dir: TempDir,
+        pub root: PathBuf,
+        pub outside: PathBuf,
+        pub fs: FileSystemCapabilities,
+    }
+
+    /// An allowed root next to a directory outside it.
+    pub(super) fn fixture() -> Fixture {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().canonicalize().unwrap();
+        let root = base.join("root");
+        let outside = base.join("outside");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::create_dir(&outside).unwrap();
+        let fs = FileSystemCapabilities::new(FileSystemConfig {
+            allowed_roots: vec![root.clone()],
+            ..FileSystemConfig::default()
+        });
+        Fixture {
+            _dir: dir,
+            root,
+            outside,
+            fs,
+        }
+    }
+
+    #[tokio::test]
+    async fn resolve_accepts_paths_inside_the_root() {
+        let f = fixture();
+        std::fs::write(f.root.join("a.txt"), "a").unwrap();
+
+        assert_eq!(f.fs.resolve(&f.root).await.unwrap(), f.root);
+        let resolved = f.fs.resolve(&f.root.join("a.txt")).await.unwrap();
+        assert_eq!(resolved, f.root.join("a.txt"));
+    }
+
+    #[tokio::test]
+    async fn resolve_rejects_paths_outside_the_root() {
+        let f = fixture();
+        std::fs::write(f.outside.join("b.txt"), "b").unwrap();
+
+        let err = f.fs.resolve(&f.outside.join("b.txt")).await.unwrap_err();
+        assert!(matches!(err, CapabilityError::OutsideAllowedRoots(_)));
+        let dotted = f.root.join("../outside/b.txt");
+        let err = f.fs.resolve(&dotted).await.unwrap_err();
+        assert!(matches!(err, CapabilityError::OutsideAllowedRoots(_)));
+    }
+
+    #[tokio::test]
+    async fn resolve_reports_missing_paths() {
+        let f = fixture();
+        let err = f.fs.resolve(&f.root.join("missing")).await.unwrap_err();
+        assert!(matches!(err, CapabilityError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn resolve_new_checks_the_parent() {
+        let f = fixture();
+
+        let new = f.fs.resolve_new(&f.root.join("new.txt")).await.unwrap();
+        assert_eq!(new, f.root.join("new.txt"));
+        let err = f.fs.resolve_new(&f.outside.join("new.txt")).await;
+        assert!(matches!(err, Err(CapabilityError::OutsideAllowedRoots(_))));
+        let err = f.fs.resolve_new(Path::new("relative.txt")).await;
+        assert!(matches!(err, Err(CapabilityError::InvalidArgument(_))));
+        let err = f.fs.resolve_new(&f.root.join("..")).await;
+        assert!(matches!(err, Err(CapabilityError::InvalidArgument(_))));
+    }
+
+    #[tokio::test]
+    async fn resolve_mutable_protects_the_root() {
+        let f = fixture();
+        let err = f.fs.resolve_mutable(&f.root).await.unwrap_err();
+        assert!(matches!(err, CapabilityError::ProtectedRoot(_)));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn resolve_rejects_symlinks_escaping_the_root() {
+        let f = fixture();
+        std::fs::write(f.outside.join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(f.outside.join("secret.txt"), f.root.join("link.txt")).unwrap();
+
+        let err = f.fs.resolve(&f.root.join("link.txt")).await.unwrap_err();
+        assert!(matches!(err, CapabilityError::OutsideAllowedRoots(_)));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn resolve_mutable_returns_the_link_not_its_target() {
+        let f = fixture();
+        std::fs::create_dir(f.root.join("real")).unwrap();
+        std::os::unix::fs::symlink(f.root.join("real"), f.root.join("dirlink")).unwrap();
+        std::os::unix::fs::symlink(&f.outside, f.root.join("outlink")).unwrap();
+
+        let link = f.fs.resolve_mutable(&f.root.join("dirlink")).await.unwrap();
+        assert_eq!(link, f.root.join("dirlink"));
+        let link = f.fs.resolve_mutable(&f.root.join("outlink")).await.unwrap();
+        assert_eq!(link, f.root.join("outlink"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn write_file_refuses_to_follow_a_symlink() {
+        let f = fixture();
+        std::fs::write(f.outside.join("target.txt"), "original").unwrap();
+        std::os::unix::fs::symlink(f.outside.join("target.txt"), f.root.join("link.txt")).unwrap();
+
+        let options = WriteOptions {
+            overwrite: true,
+            create_parents: false,
+        };
+        let result =
+            f.fs.write_file(&f.root.join("link.txt"), b"pwned", options)
+                .await;
+        assert!(matches!(result, Err(CapabilityError::InvalidArgument(_))));
+        let contents = std::fs::read_to_string(f.outside.join("target.txt")).unwrap();
+        assert_eq!(contents, "original");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn delete_removes_a_symlink_and_keeps_its_target() {
+        let f = fixture();
+        std::fs::create_dir(f.root.join("real")).unwrap();
+        std::fs::write(f.root.join("real/keep.txt"), "keep").unwrap();
+        std::os::unix::fs::symlink(f.root.join("real"), f.root.join("dirlink")).unwrap();
+
+        f.fs.delete(&f.root.join("dirlink"), true, false)
+            .await
+            .unwrap();
+        assert!(std::fs::symlink_metadata(f.root.join("dirlink")).is_err());
+        assert!(f.root.join("real/keep.txt").exists());
+    }
+}
@@ -0,0 +1,223 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::FileSystemCapabilities;
+use crate::error::{CapabilityError, Result};
+
+/// What a directory looks like as a project, inferred from marker files.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectInfo {
+    pub path: PathBuf,
+    /// Package name from the manifest, else the directory name.
+    pub name: String,
+    pub languages: Vec<String>,
+    pub build_tools: Vec<String>,
+    /// Version control system of the directory or an enclosing one.
+    pub vcs: Option<String>,
+}
+
+/// Marker file, and the language and build tool it implies.
+const MARKERS: &[(&str, Option<&str>, Option<&str>)] = &[
+    ("Cargo.toml", Some("rust"), Some("cargo")),
+    ("package.json", Some("javascript"), None),
+    ("tsconfig.json", Some("typescript"), None),
+    ("pyproject.toml", Some("python"), None),
+    ("setup.py", Some("python"), Some("setuptools")),
+    ("requirements.txt", Some("python"), Some("pip")),
+    ("go.mod", Some("go"), Some("go")),
+    ("pom.xml", Some("java"), Some("maven")),
+    ("build.gradle", Some("java"), Some("gradle")),
+    ("build.gradle.kts", Some("kotlin"), Some("gradle")),
+    ("Gemfile", Some("ruby"), Some("bundler")),
+    ("composer.json", Some("php"), Some("composer")),
+    ("CMakeLists.txt", Some("c++"), Some("cmake")),
+    ("Makefile", None, Some("make")),
+];
+
+/// Lock files that identify the JavaScript package manager, in order of
+/// precedence.
+const JS_LOCK_FILES: &[(&str, &str)] = &[
+    ("pnpm-lock.yaml", "pnpm"),
+    ("yarn.lock", "yarn"),
+    ("bun.lockb", "bun"),
+    ("bun.lock", "bun"),
+];
+
+const VCS_MARKERS: &[(&str, &str)] = &[(".git", "git"), (".hg", "mercurial"), (".svn", "svn")];
+
+impl FileSystemCapabilities {
+    /// Inspects the directory at `path` for language and tooling markers.
+    /// Only the directory itself is examined, except for version control,
+    /// which is also looked up in its ancestors.
+    pub async fn detect_project(&self, path: &Path) -> Result<ProjectInfo> {
+        let path = self.resolve(path).await?;
+        if !tokio::fs::metadata(&path).await?.is_dir() {
+            return Err(CapabilityError::InvalidArgument(format!(
+                "not a directory: {}",
+                path.display()
+            )));
+        }
+        tokio::task::spawn_blocking(move || detect(path))
+            .await
+            .map_err(|err| CapabilityError::Io(std::io::Error::other(err)))
+    }
+}
+
+fn detect(path: PathBuf) -> ProjectInfo {
+    let has = |name: &str| path.join(name).exists();
+    let mut languages = Vec::new();
+    let mut build_tools = Vec::new();
+
+    for (marker, language, tool) in MARKERS {
+        if !has(marker) {
+            continue;
+        }
+        if let Some(language) = language {
+            push_unique(&mut languages, language);
+        }
+        if let Some(tool) = tool {
+            push_unique(&mut build_tools, tool);
+        }
+    }
+    if has("package.json") {
+        let manager = JS_LOCK_FILES
+            .iter()
+            .find(|(lock, _)| has(lock))
+            .map_or("npm", |(_, manager)| manager);
+        push_unique(&mut build_tools, manager);
+    }
+    if let Some(tool) = python_tool(&path) {
+        push_unique(&mut build_tools, tool);
+    }
+
+    let vcs = path.ancestors().find_map(|dir| {
+        VCS_MARKERS
+            .iter()
+            .find(|(marker, _)| dir.join(marker).exists())
+            .map(|(_, vcs)| vcs.to_string())
+    });
+
+    let name = manifest_name(&path).unwrap_or_else(|| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string())
+    });
+
+    ProjectInfo {
+        path,
+        name,
+        languages,
+        build_tools,
+        vcs,
+    }
+}
+
+fn push_unique(items: &mut Vec<String>, item: &str) {
+    if !items.iter().any(|existing| existing == item) {
+        items.push(item.to_string());
+    }
+}
+
+/// Build tool behind a `pyproject.toml`, if it says.
+fn python_tool(path: &Path) -> Option<&'static str> {
+    let pyproject = read_toml(&path.join("pyproject.toml"))?;
+    if path.join("uv.lock").exists() {
+        Some("uv")
+    } else if pyproject
+        .get("tool")
+        .and_then(|tool| tool.get("poetry"))
+        .is_some()
+    {
+        Some("poetry")
+    } else if pyproject
+        .get("tool")
+        .and_then(|tool| tool.get("hatch"))
+        .is_some()
+    {
+        Some("hatch")
+    } else {
+        Some("pip")
+    }
+}
+
+/// Package name declared by the first manifest that has one.
+fn manifest_name(path: &Path) -> Option<String> {
+    let toml_name = |file: &str, keys: &[&[&str]]| {
+        let manifest = read_toml(&path.join(file))?;
+        keys.iter().find_map(|keys| {
+            keys.iter()
+                .try_fold(&manifest, |value, key| value.get(key))
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+        })
+    };
+
+    toml_name(
+        "Cargo.toml",
+        &[&["package", "name"], &["workspace", "package", "name"]],
+    )
+    .or_else(|| {
+        let contents = std::fs::read(path.join("package.json")).ok()?;
+        let manifest: serde_json::Value = serde_json::from_slice(&contents).ok()?;
+        manifest.get("name")?.as_str().map(str::to_string)
+    })
+    .or_else(|| {
+        toml_name(
+            "pyproject.toml",
+            &[&["project", "name"], &["tool", "poetry", "name"]],
+        )
+    })
+    .or_else(|| {
+        let contents = std::fs::read_to_string(path.join("go.mod")).ok()?;
+        contents
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("module "))
+            .map(|module| module.trim().trim_matches('"').to_string())
+    })
+}
+
+fn read_toml(path: &Path) -> Option<toml::Value> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    toml::from_str(&contents).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::fixture;
+    use super::*;
+
+    #[tokio::test]
+    async fn detect_project_reads_markers_and_manifest_name() {
+        let f = fixture();
+        std::fs::create_dir(f.root.join(".git")).unwrap();
+        let app = f.root.join("app");
+        std::fs::create_dir(&app).unwrap();
+        std::fs::write(app.join("Cargo.toml"), "[package]\nname = \"demo\"\n").unwrap();
+        std::fs::write(app.join("package.json"), r#"{"name": "demo-ui"}"#).unwrap();
+        std::fs::write(app.join("pnpm-lock.yaml"), "").unwrap();
+
+        let project = f.fs.detect_project(&app).await.unwrap();
+        assert_eq!(project.name, "demo");
+        assert_eq!(project.languages, ["rust", "javascript"]);
+        assert_eq!(project.build_tools, ["cargo", "pnpm"]);
+        // Found in the enclosing directory.
+        assert_eq!(project.vcs.as_deref(), Some("git"));
+    }
+
+    #[tokio::test]
+    async fn detect_project_falls_back_to_the_directory_name() {
+        let f = fixture();
+        let dir = f.root.join("plain");
+        std::fs::create_dir(&dir).unwrap();
+        std::fs::write(dir.join("Cargo.toml"), "not toml [").unwrap();
+
+        let project = f.fs.detect_project(&dir).await.unwrap();
+        assert_eq!(project.name, "plain");
+        assert_eq!(project.vcs, None);
+
+        let file = dir.join("Cargo.toml");
+        let err = f.fs.detect_project(&file).await.unwrap_err();
+        assert!(matches!(err, CapabilityError::InvalidArgument(_)));
+    }
+}
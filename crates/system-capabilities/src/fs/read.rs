@@ -0,0 +1,198 @@
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::Serialize;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use super::sniff::{self, SNIFF_LEN};
+use super::{FileSystemCapabilities, open_regular};
+use crate::error::{CapabilityError, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileEncoding {
+    /// Valid UTF-8, returned verbatim.
+    Utf8,
+    /// Text with invalid UTF-8 sequences replaced by U+FFFD.
+    Utf8Lossy,
+    /// Binary data, returned base64-encoded.
+    Binary,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileContent {
+    pub path: PathBuf,
+    /// Total size of the file in bytes.
+    pub size: u64,
+    /// Byte offset the returned content starts at.
+    pub offset: u64,
+    /// Number of bytes of the file covered by `content`.
+    pub length: u64,
+    pub encoding: FileEncoding,
+    pub content: String,
+    /// Whether bytes remain after `offset + length`.
+    pub truncated: bool,
+}
+
+impl FileSystemCapabilities {
+    /// Reads up to `limit` bytes of the file at `path` starting at `offset`.
+    /// `limit` defaults to, and is capped at, the configured maximum read
+    /// size.
+    pub async fn read_file(
+        &self,
+        path: &Path,
+        offset: u64,
+        limit: Option<u64>,
+    ) -> Result<FileContent> {
+        let path = self.resolve(path).await?;
+        let metadata = tokio::fs::metadata(&path).await?;
+        if !metadata.is_file() {
+            return Err(CapabilityError::NotAFile(path));
+        }
+
+        let size = metadata.len();
+        if offset > size {
+            return Err(CapabilityError::InvalidArgument(format!(
+                "offset {offset} is past the end of the file ({size} bytes)"
+            )));
+        }
+
+        if limit == Some(0) {
+            return Err(CapabilityError::InvalidArgument(
+                "limit must be at least 1".into(),
+            ));
+        }
+        let max = self.config.max_read_bytes;
+        let limit = limit.unwrap_or(max).min(max);
+        let to_read = limit.min(size - offset);
+
+        let mut file = open_regular(&path).await?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        let mut bytes = Vec::with_capacity(to_read as usize);
+        file.take(to_read).read_to_end(&mut bytes).await?;
+
+        let truncated = offset + (bytes.len() as u64) < size;
+        let (encoding, content, length) = decode(bytes, truncated);
+
+        Ok(FileContent {
+            path,
+            size,
+            offset,
+            length,
+            encoding,
+            content,
+            truncated,
+        })
+    }
+}
+
+/// Decodes `bytes`, returning the detected encoding, the content and how
+/// many bytes it covers. When the range ends mid-character, the incomplete
+/// trailing sequence is left for the next read instead of being mangled,
+/// unless it is all there is: holding it back would report a length of zero
+/// and leave a client paging by `offset += length` stuck.
+fn decode(mut bytes: Vec<u8>, truncated: bool) -> (FileEncoding, String, u64) {
+    if sniff::looks_binary(&bytes[..bytes.len().min(SNIFF_LEN)]) {
+        let length = bytes.len() as u64;
+        return (FileEncoding::Binary, BASE64.encode(&bytes), length);
+    }
+
+    match std::str::from_utf8(&bytes) {
+        Ok(_) => {}
+        Err(err) if truncated && err.error_len().is_none() && err.valid_up_to() > 0 => {
+            bytes.truncate(err.valid_up_to())
+        }
+        Err(_) => {
+            let length = bytes.len() as u64;
+            let content = String::from_utf8_lossy(&bytes).into_owned();
+            return (FileEncoding::Utf8Lossy, content, length);
+        }
+    }
+
+    let length = bytes.len() as u64;
+    let content = String::from_utf8(bytes).unwrap_or_default();
+    (FileEncoding::Utf8, content, length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::fixture;
+    use super::*;
+
+    #[tokio::test]
+    async fn read_file_pages_through_multibyte_text_with_tiny_limits() {
+        let f = fixture();
+        let path = f.root.join("accents.txt");
+        std::fs::write(&path, "éé").unwrap();
+
+        let mut offset = 0;
+        let mut pages = 0;
+        loop {
+            let page = f.fs.read_file(&path, offset, Some(1)).await.unwrap();
+            assert!(page.length > 0);
+            offset += page.length;
+            pages += 1;
+            if !page.truncated {
+                break;
+            }
+        }
+        assert_eq!((offset, pages), (4, 4));
+
+        let err = f.fs.read_file(&path, 0, Some(0)).await.unwrap_err();
+        assert!(matches!(err, CapabilityError::InvalidArgument(_)));
+    }
+
+    #[test]
+    fn decode_returns_complete_utf8_verbatim() {
+        let (encoding, content, length) = decode("héllo".as_bytes().to_vec(), false);
+        assert!(matches!(encoding, FileEncoding::Utf8));
+        assert_eq!(content, "héllo");
+        assert_eq!(length, 6);
+    }
+
+    #[test]
+    fn decode_holds_back_a_character_split_by_the_range() {
+        // "hé" cut after the first byte of the two-byte "é".
+        let bytes = "hé".as_bytes()[..2].to_vec();
+        let (encoding, content, length) = decode(bytes, true);
+        assert!(matches!(encoding, FileEncoding::Utf8));
+        assert_eq!(content, "h");
+        assert_eq!(length, 1);
+    }
+
+    #[test]
+    fn decode_replaces_a_split_character_at_end_of_file() {
+        let bytes = "hé".as_bytes()[..2].to_vec();
+        let (encoding, content, length) = decode(bytes, false);
+        assert!(matches!(encoding, FileEncoding::Utf8Lossy));
+        assert_eq!(content, "h\u{fffd}");
+        assert_eq!(length, 2);
+    }
+
+    #[test]
+    fn decode_never_holds_back_the_whole_range() {
+        // A one byte range over the two-byte "é".
+        let bytes = "é".as_bytes()[..1].to_vec();
+        let (encoding, content, length) = decode(bytes, true);
+        assert!(matches!(encoding, FileEncoding::Utf8Lossy));
+        assert_eq!(content, "\u{fffd}");
+        assert_eq!(length, 1);
+    }
+
+    #[test]
+    fn decode_replaces_invalid_sequences_mid_range() {
+        let (encoding, content, _) = decode(b"caf\xe9 au lait".to_vec(), true);
+        assert!(matches!(encoding, FileEncoding::Utf8Lossy));
+        assert_eq!(content, "caf\u{fffd} au lait");
+    }
+
+    #[test]
+    fn decode_base64_encodes_binary() {
+        let (encoding, content, length) = decode(vec![0, 1, 2, 3], false);
+        assert!(matches!(encoding, FileEncoding::Binary));
+        assert_eq!(content, "AAECAw==");
+        assert_eq!(length, 4);
+    }
+}
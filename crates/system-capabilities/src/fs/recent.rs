@@ -0,0 +1,197 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use super::{FileInfo, FileSystemCapabilities};
+use crate::error::{CapabilityError, Result};
+
+/// Dependency and build output directories skipped by the walk; their
+/// churn is rarely what the user wants to see.
+const IGNORED_DIRS: &[&str] = &["node_modules", "target", "__pycache__"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentFiles {
+    /// Most recently modified first.
+    pub files: Vec<FileInfo>,
+    pub scanned: usize,
+    /// Whether the walk stopped early because it ran out of time or hit the
+    /// configured entry limit, in which case files may be missing.
+    pub incomplete: bool,
+}
+
+impl FileSystemCapabilities {
+    /// Files under `base` modified after `since` (seconds since the Unix
+    /// epoch), most recently modified first. Hidden entries (including the
+    /// trash and `.git`), dependency and build directories, and symlinks are
+    /// skipped. The walk shares the search time budget and entry limit, and
+    /// only the newest `limit` files are kept while it runs.
+    pub async fn recent_files(&self, base: &Path, since: u64, limit: usize) -> Result<RecentFiles> {
+        let base = self.resolve(base).await?;
+        let budget = Duration::from_millis(self.config.max_search_budget_ms);
+        let max_entries = self.config.max_search_entries;
+        tokio::task::spawn_blocking(move || walk(base, since, limit, budget, max_entries))
+            .await
+            .map_err(|err| CapabilityError::Io(std::io::Error::other(err)))
+    }
+}
+
+/// A file ordered by modification time, then by path descending so that
+/// among equally old files the later path is evicted first. The heap holds
+/// `Reverse<Candidate>`, putting the entry to evict on top.
+struct Candidate {
+    key: (u64, Reverse<PathBuf>),
+    info: FileInfo,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+fn walk(
+    base: PathBuf,
+    since: u64,
+    limit: usize,
+    budget: Duration,
+    max_entries: usize,
+) -> RecentFiles {
+    let started = Instant::now();
+    let mut newest: BinaryHeap<Reverse<Candidate>> = BinaryHeap::new();
+    let mut scanned = 0;
+    let mut incomplete = false;
+
+    let mut queue = VecDeque::from([base]);
+    'walk: while let Some(dir) = queue.pop_front() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if scanned >= max_entries || started.elapsed() >= budget {
+                incomplete = true;
+                break 'walk;
+            }
+            scanned += 1;
+
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                if !IGNORED_DIRS.contains(&name.as_ref()) {
+                    queue.push_back(entry.path());
+                }
+                continue;
+            }
+            if !metadata.is_file() {
+                continue;
+            }
+            let info = FileInfo::from_metadata(entry.path(), &metadata);
+            let Some(modified) = info.modified.filter(|&modified| modified > since) else {
+                continue;
+            };
+            let candidate = Candidate {
+                key: (modified, Reverse(info.path.clone())),
+                info,
+            };
+            if newest.len() == limit {
+                match newest.peek() {
+                    Some(Reverse(oldest)) if candidate > *oldest => {
+                        newest.pop();
+                    }
+                    _ => continue,
+                }
+            }
+            newest.push(Reverse(candidate));
+        }
+    }
+
+    // Ascending order of `Reverse` is newest first.
+    let files = newest
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(candidate)| candidate.info.sniff_blocking())
+        .collect();
+    RecentFiles {
+        files,
+        scanned,
+        incomplete,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use super::super::tests::fixture;
+    use super::*;
+
+    fn write_aged(path: &Path, age: Duration) {
+        std::fs::write(path, "").unwrap();
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    #[tokio::test]
+    async fn recent_files_keeps_the_newest_files_after_since() {
+        let f = fixture();
+        std::fs::create_dir(f.root.join("sub")).unwrap();
+        write_aged(&f.root.join("old.txt"), Duration::from_secs(3600));
+        write_aged(&f.root.join("older.txt"), Duration::from_secs(30));
+        write_aged(&f.root.join("sub/newer.txt"), Duration::from_secs(20));
+        write_aged(&f.root.join("newest.txt"), Duration::from_secs(10));
+
+        let since = now() - 60;
+        let recent = f.fs.recent_files(&f.root, since, 10).await.unwrap();
+        let names: Vec<_> = recent.files.iter().map(|file| file.name.as_str()).collect();
+        assert_eq!(names, ["newest.txt", "newer.txt", "older.txt"]);
+        assert!(!recent.incomplete);
+
+        let recent = f.fs.recent_files(&f.root, since, 2).await.unwrap();
+        let names: Vec<_> = recent.files.iter().map(|file| file.name.as_str()).collect();
+        assert_eq!(names, ["newest.txt", "newer.txt"]);
+    }
+
+    #[tokio::test]
+    async fn recent_files_skips_hidden_and_dependency_directories() {
+        let f = fixture();
+        for dir in [".git", "node_modules", "target"] {
+            std::fs::create_dir(f.root.join(dir)).unwrap();
+            write_aged(&f.root.join(dir).join("x"), Duration::ZERO);
+        }
+        write_aged(&f.root.join(".hidden"), Duration::ZERO);
+        write_aged(&f.root.join("visible"), Duration::ZERO);
+
+        let recent = f.fs.recent_files(&f.root, now() - 60, 10).await.unwrap();
+        let names: Vec<_> = recent.files.iter().map(|file| file.name.as_str()).collect();
+        assert_eq!(names, ["visible"]);
+    }
+}
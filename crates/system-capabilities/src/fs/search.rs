@@ -0,0 +1,208 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use super::{FileInfo, FileSystemCapabilities};
+use crate::error::Result;
+
+const CHANNEL_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    /// Case-insensitive substring matched against entry names.
+    pub query: String,
+    pub max_results: usize,
+    pub max_depth: Option<usize>,
+    /// Descend into and match entries whose name starts with a dot.
+    pub include_hidden: bool,
+    /// Time after which the walk gives up; capped by the configured maximum.
+    pub budget: Duration,
+}
+
+/// One record of a search stream: matches as they are found, then a single
+/// summary once the walk ends.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SearchEvent {
+    Match(FileInfo),
+    Summary(SearchSummary),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchSummary {
+    pub matches: usize,
+    pub scanned: usize,
+    /// Whether the walk stopped early because `max_results` was reached.
+    pub truncated: bool,
+    /// Whether the walk stopped early because it ran out of time or hit the
+    /// configured entry limit, in which case matches may be missing.
+    pub incomplete: bool,
+    pub elapsed_ms: u64,
+}
+
+impl FileSystemCapabilities {
+    /// Walks `base` on a blocking thread, streaming [`SearchEvent`]s. The
+    /// walk stops once the receiver is dropped, checked before each
+    /// directory, or when the time budget or entry limit runs out.
+    pub async fn search(
+        &self,
+        base: &Path,
+        mut options: SearchOptions,
+    ) -> Result<mpsc::Receiver<SearchEvent>> {
+        let base = self.resolve(base).await?;
+        options.budget = options
+            .budget
+            .min(Duration::from_millis(self.config.max_search_budget_ms));
+        let max_entries = self.config.max_search_entries;
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        tokio::task::spawn_blocking(move || walk(base, options, max_entries, tx));
+        Ok(rx)
+    }
+}
+
+fn walk(base: PathBuf, options: SearchOptions, max_entries: usize, tx: mpsc::Sender<SearchEvent>) {
+    let started = Instant::now();
+    let needle = options.query.to_lowercase();
+    let mut matches = 0;
+    let mut scanned = 0;
+    let mut truncated = false;
+    let mut incomplete = false;
+
+    let mut queue = VecDeque::from([(base, 0usize)]);
+    'walk: while let Some((dir, depth)) = queue.pop_front() {
+        // Sends only happen on matches, so a rare query would otherwise
+        // never notice the client leaving.
+        if tx.is_closed() {
+            return;
+        }
+        if started.elapsed() >= options.budget || scanned >= max_entries {
+            incomplete = true;
+            break;
+        }
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !options.include_hidden && name.starts_with('.') {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            scanned += 1;
+
+            let path = entry.path();
+            // DirEntry::metadata does not follow symlinks, so linked
+            // directories are never descended into.
+            if metadata.is_dir() && options.max_depth.is_none_or(|max| depth < max) {
+                queue.push_back((path.clone(), depth + 1));
+            }
+
+            if name.to_lowercase().contains(&needle) {
+                if matches == options.max_results {
+                    truncated = true;
+                    break 'walk;
+                }
+                matches += 1;
+                let info = FileInfo::from_metadata(path, &metadata).sniff_blocking();
+                let event = SearchEvent::Match(info);
+                if tx.blocking_send(event).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    let summary = SearchSummary {
+        matches,
+        scanned,
+        truncated,
+        incomplete,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    };
+    let _ = tx.blocking_send(SearchEvent::Summary(summary));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::fixture;
+    use super::*;
+
+    fn options(query: &str) -> SearchOptions {
+        SearchOptions {
+            query: query.to_string(),
+            max_results: 100,
+            max_depth: None,
+            include_hidden: false,
+            budget: Duration::from_secs(10),
+        }
+    }
+
+    async fn collect(mut events: mpsc::Receiver<SearchEvent>) -> (Vec<PathBuf>, SearchSummary) {
+        let mut matches = Vec::new();
+        while let Some(event) = events.recv().await {
+            match event {
+                SearchEvent::Match(info) => matches.push(info.path),
+                SearchEvent::Summary(summary) => {
+                    matches.sort();
+                    return (matches, summary);
+                }
+            }
+        }
+        panic!("search ended without a summary");
+    }
+
+    #[tokio::test]
+    async fn search_matches_names_case_insensitively() {
+        let f = fixture();
+        std::fs::create_dir_all(f.root.join("src/Deep")).unwrap();
+        std::fs::write(f.root.join("src/Deep/Main.rs"), "").unwrap();
+        std::fs::write(f.root.join("main.txt"), "").unwrap();
+        std::fs::write(f.root.join(".main"), "").unwrap();
+        std::fs::write(f.root.join("other.rs"), "").unwrap();
+
+        let events = f.fs.search(&f.root, options("MAIN")).await.unwrap();
+        let (matches, summary) = collect(events).await;
+        assert_eq!(
+            matches,
+            [f.root.join("main.txt"), f.root.join("src/Deep/Main.rs")]
+        );
+        assert!(!summary.truncated && !summary.incomplete);
+
+        let shallow = SearchOptions {
+            max_depth: Some(0),
+            include_hidden: true,
+            ..options("main")
+        };
+        let (matches, _) = collect(f.fs.search(&f.root, shallow).await.unwrap()).await;
+        assert_eq!(matches, [f.root.join(".main"), f.root.join("main.txt")]);
+    }
+
+    #[tokio::test]
+    async fn search_stops_at_its_limits() {
+        let f = fixture();
+        for i in 0..5 {
+            std::fs::write(f.root.join(format!("file{i}")), "").unwrap();
+        }
+
+        let limited = SearchOptions {
+            max_results: 2,
+            ..options("file")
+        };
+        let (matches, summary) = collect(f.fs.search(&f.root, limited).await.unwrap()).await;
+        assert_eq!(matches.len(), 2);
+        assert!(summary.truncated);
+
+        let out_of_time = SearchOptions {
+            budget: Duration::ZERO,
+            ..options("file")
+        };
+        let (matches, summary) = collect(f.fs.search(&f.root, out_of_time).await.unwrap()).await;
+        assert!(matches.is_empty());
+        assert!(summary.incomplete);
+    }
+}
@@ -0,0 +1,142 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use super::FileSystemCapabilities;
+use crate::error::{CapabilityError, Result};
+
+const CHANNEL_CAPACITY: usize = 16;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct DirectorySize {
+    pub bytes: u64,
+    pub files: u64,
+    pub directories: u64,
+}
+
+/// Progress of a size computation: periodic running totals, then a final
+/// record.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DirectorySizeEvent {
+    Progress(DirectorySize),
+    Done {
+        #[serde(flatten)]
+        size: DirectorySize,
+        /// False when the time budget ran out before the walk finished, in
+        /// which case the totals are a lower bound.
+        complete: bool,
+        elapsed_ms: u64,
+    },
+}
+
+impl FileSystemCapabilities {
+    /// Computes the total size and entry counts under `path` on a blocking
+    /// thread, giving up once `budget` has elapsed. Symlinks are counted but
+    /// not followed. The walk stops as soon as the receiver is dropped.
+    pub async fn directory_size(
+        &self,
+        path: &Path,
+        budget: Duration,
+    ) -> Result<mpsc::Receiver<DirectorySizeEvent>> {
+        let path = self.resolve(path).await?;
+        if !tokio::fs::metadata(&path).await?.is_dir() {
+            return Err(CapabilityError::InvalidArgument(format!(
+                "not a directory: {}",
+                path.display()
+            )));
+        }
+        let budget = budget.min(Duration::from_millis(self.config.max_size_budget_ms));
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        tokio::task::spawn_blocking(move || walk(path, budget, tx));
+        Ok(rx)
+    }
+}
+
+fn walk(path: PathBuf, budget: Duration, tx: mpsc::Sender<DirectorySizeEvent>) {
+    let started = Instant::now();
+    let mut last_progress = started;
+    let mut size = DirectorySize::default();
+    let mut complete = true;
+
+    let mut stack = vec![path];
+    while let Some(dir) = stack.pop() {
+        if started.elapsed() >= budget {
+            complete = false;
+            break;
+        }
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            if tx
+                .blocking_send(DirectorySizeEvent::Progress(size))
+                .is_err()
+            {
+                return;
+            }
+        }
+
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                size.directories += 1;
+                stack.push(entry.path());
+            } else {
+                size.files += 1;
+                size.bytes += metadata.len();
+            }
+        }
+    }
+
+    let _ = tx.blocking_send(DirectorySizeEvent::Done {
+        size,
+        complete,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::fixture;
+    use super::*;
+
+    #[tokio::test]
+    async fn directory_size_totals_a_tree() {
+        let f = fixture();
+        std::fs::create_dir(f.root.join("sub")).unwrap();
+        std::fs::write(f.root.join("a.txt"), "abc").unwrap();
+        std::fs::write(f.root.join("sub/b.txt"), "de").unwrap();
+
+        let mut events =
+            f.fs.directory_size(&f.root, Duration::from_secs(10))
+                .await
+                .unwrap();
+        let mut last = None;
+        while let Some(event) = events.recv().await {
+            last = Some(event);
+        }
+        let Some(DirectorySizeEvent::Done { size, complete, .. }) = last else {
+            panic!("walk ended without a done event");
+        };
+        assert!(complete);
+        assert_eq!((size.bytes, size.files), (5, 2));
+    }
+
+    #[tokio::test]
+    async fn directory_size_rejects_files() {
+        let f = fixture();
+        std::fs::write(f.root.join("a.txt"), "abc").unwrap();
+
+        let result =
+            f.fs.directory_size(&f.root.join("a.txt"), Duration::from_secs(10))
+                .await;
+        assert!(matches!(result, Err(CapabilityError::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,159 @@
+use std::io::Read;
+use std::path::Path;
+
+use tokio::io::AsyncReadExt;
+
+use super::{open_regular, open_regular_blocking};
+
+/// Bytes read from the start of a file to detect its content type.
+pub(crate) const SNIFF_LEN: usize = 8 * 1024;
+
+/// Detected content type of a file.
+pub(crate) struct ContentType {
+    pub mime_type: String,
+    pub is_binary: bool,
+}
+
+/// Detects the content type from the leading bytes of a file: known
+/// magic numbers first, then a text/binary heuristic, using the extension
+/// only to refine the type of text files.
+pub(crate) fn detect(path: &Path, head: &[u8]) -> ContentType {
+    if let Some(kind) = infer::get(head) {
+        let mime_type = kind.mime_type().to_string();
+        let is_binary = !mime_type.starts_with("text/");
+        return ContentType {
+            mime_type,
+            is_binary,
+        };
+    }
+
+    if looks_binary(head) {
+        return ContentType {
+            mime_type: "application/octet-stream".to_string(),
+            is_binary: true,
+        };
+    }
+
+    let guessed = mime_guess::from_path(path)
+        .iter()
+        .find(|mime| is_textual(mime.essence_str()));
+    ContentType {
+        mime_type: guessed.map_or_else(|| "text/plain".to_string(), |mime| mime.to_string()),
+        is_binary: false,
+    }
+}
+
+/// NUL bytes mark binary data, as do control characters: more than 10% of
+/// valid UTF-8, or more than 1% of content that is not UTF-8 (which
+/// tolerates legacy-encoded text but not random bytes).
+pub(crate) fn looks_binary(head: &[u8]) -> bool {
+    if head.contains(&0) {
+        return true;
+    }
+    let control = head
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\n' | b'\r' | b'\t' | 0x0c | 0x1b))
+        .count();
+    let valid_utf8 = match std::str::from_utf8(head) {
+        Ok(_) => true,
+        // A multi-byte character cut off at the end of `head`.
+        Err(err) => err.error_len().is_none(),
+    };
+    let threshold = if valid_utf8 { 10 } else { 100 };
+    control * threshold > head.len()
+}
+
+fn is_textual(essence: &str) -> bool {
+    essence.starts_with("text/")
+        || essence.ends_with("+xml")
+        || essence.ends_with("+json")
+        || matches!(
+            essence,
+            "application/json"
+                | "application/javascript"
+                | "application/xml"
+                | "application/toml"
+                | "application/x-sh"
+                | "application/x-yaml"
+        )
+}
+
+/// Reads the first bytes of a regular file; see [`open_regular_blocking`].
+pub(crate) fn read_head_blocking(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    open_regular_blocking(path)?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut head)?;
+    Ok(head)
+}
+
+pub(crate) async fn read_head(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    open_regular(path)
+        .await?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut head)
+        .await?;
+    Ok(head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_binary_accepts_text() {
+        assert!(!looks_binary(b"fn main() {\n\tprintln!(\"hi\");\n}\n"));
+        assert!(!looks_binary("naïve café ✓".as_bytes()));
+        assert!(!looks_binary(b"\x1b[31mred\x1b[0m\r\n"));
+        assert!(!looks_binary(b""));
+    }
+
+    #[test]
+    fn looks_binary_tolerates_legacy_encodings() {
+        assert!(!looks_binary(b"caf\xe9 cr\xe8me br\xfbl\xe9e"));
+    }
+
+    #[test]
+    fn looks_binary_tolerates_a_character_cut_off_at_the_end() {
+        let head = &"text é".as_bytes()[.."text é".len() - 1];
+        assert!(!looks_binary(head));
+    }
+
+    #[test]
+    fn looks_binary_flags_nul_bytes_and_control_characters() {
+        assert!(looks_binary(b"text\0more text"));
+        assert!(looks_binary(&[0x01, 0x02, 0x03, b'a', b'b', b'c']));
+        assert!(looks_binary(b"\xff\xfe\x01\x80\x90\xa0\x02\xb0"));
+    }
+
+    #[test]
+    fn detect_prefers_magic_numbers() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        let content = detect(Path::new("image.txt"), png);
+        assert_eq!(content.mime_type, "image/png");
+        assert!(content.is_binary);
+    }
+
+    #[test]
+    fn detect_uses_the_extension_for_text() {
+        let content = detect(Path::new("data.json"), b"{\"a\": 1}");
+        assert_eq!(content.mime_type, "application/json");
+        assert!(!content.is_binary);
+
+        let content = detect(Path::new("main.rs"), b"fn main() {}\n");
+        assert!(content.mime_type.starts_with("text/"));
+        assert!(!content.is_binary);
+    }
+
+    #[test]
+    fn detect_falls_back_to_plain_text_or_octet_stream() {
+        let content = detect(Path::new("README"), b"hello\n");
+        assert_eq!(content.mime_type, "text/plain");
+        assert!(!content.is_binary);
+
+        let content = detect(Path::new("notes.txt"), b"\x01\x02\x03\x04\0");
+        assert_eq!(content.mime_type, "application/octet-stream");
+        assert!(content.is_binary);
+    }
+}
@@ -0,0 +1,119 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+
+use super::{FileInfo, FileSystemCapabilities, open_regular};
+use crate::error::{CapabilityError, Result};
+
+/// A file being received in chunks. Data goes to a temporary sibling file
+/// that is renamed over the target by [`Upload::finish`]; dropping an
+/// unfinished upload removes the temporary file.
+pub struct Upload {
+    target: PathBuf,
+    temp: PathBuf,
+    file: Option<File>,
+    written: u64,
+    limit: u64,
+    overwrite: bool,
+}
+
+impl FileSystemCapabilities {
+    /// Starts an upload of a file named `file_name` into the directory `dir`.
+    /// Only the final component of `file_name` is used.
+    pub async fn begin_upload(
+        &self,
+        dir: &Path,
+        file_name: &str,
+        overwrite: bool,
+    ) -> Result<Upload> {
+        let name = Path::new(file_name)
+            .file_name()
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| {
+                CapabilityError::InvalidArgument(format!("invalid file name: {file_name}"))
+            })?;
+
+        let dir = self.resolve(dir).await?;
+        if !tokio::fs::metadata(&dir).await?.is_dir() {
+            return Err(CapabilityError::InvalidArgument(format!(
+                "not a directory: {}",
+                dir.display()
+            )));
+        }
+
+        let target = dir.join(name);
+        if !overwrite && tokio::fs::symlink_metadata(&target).await.is_ok() {
+            return Err(CapabilityError::AlreadyExists(target));
+        }
+
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let temp = dir.join(format!(".{}.{stamp}.upload", name.to_string_lossy()));
+        let file = File::create_new(&temp).await?;
+
+        Ok(Upload {
+            target,
+            temp,
+            file: Some(file),
+            written: 0,
+            limit: self.config.max_upload_bytes,
+            overwrite,
+        })
+    }
+
+    /// Opens a file for streaming to a client, returning its metadata and
+    /// detected content type.
+    pub async fn open_for_download(&self, path: &Path) -> Result<(FileInfo, File)> {
+        let path = self.resolve(path).await?;
+        let file = match open_regular(&path).await {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::InvalidInput => {
+                return Err(CapabilityError::NotAFile(path));
+            }
+            Err(err) => return Err(err.into()),
+        };
+        Ok((FileInfo::load(path).await?, file))
+    }
+}
+
+impl Upload {
+    pub fn target(&self) -> &Path {
+        &self.target
+    }
+
+    pub async fn write_chunk(&mut self, chunk: &[u8]) -> Result<()> {
+        self.written += chunk.len() as u64;
+        if self.written > self.limit {
+            return Err(CapabilityError::TooLarge { limit: self.limit });
+        }
+        let file = self.file.as_mut().expect("upload already finished");
+        file.write_all(chunk).await?;
+        Ok(())
+    }
+
+    /// Flushes the data and moves it into place.
+    pub async fn finish(mut self) -> Result<FileInfo> {
+        let mut file = self.file.take().expect("upload already finished");
+        file.flush().await?;
+        file.sync_all().await?;
+        drop(file);
+
+        if !self.overwrite && tokio::fs::symlink_metadata(&self.target).await.is_ok() {
+            return Err(CapabilityError::AlreadyExists(self.target.clone()));
+        }
+        tokio::fs::rename(&self.temp, &self.target).await?;
+        FileInfo::load(self.target.clone()).await
+    }
+}
+
+impl Drop for Upload {
+    fn drop(&mut self) {
+        // The temporary file is gone once finish() renamed it, so this only
+        // cleans up uploads that failed or were abandoned.
+        let _ = std::fs::remove_file(&self.temp);
+    }
+}
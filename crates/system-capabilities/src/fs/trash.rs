@@ -0,0 +1,288 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::{FileInfo, FileSystemCapabilities};
+use crate::error::{CapabilityError, Result};
+
+/// Directory, created inside each allowed root, holding trashed entries.
+pub const TRASH_DIR: &str = ".jisi-trash";
+
+/// Each trashed entry gets its own directory in the trash, holding the entry
+/// itself under [`ITEM`] and its [`TrashEntry`] record under [`METADATA`].
+const ITEM: &str = "item";
+const METADATA: &str = "meta.json";
+
+/// A trashed file or directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: String,
+    pub original_path: PathBuf,
+    /// Deletion time in seconds since the Unix epoch.
+    pub deleted_at: u64,
+    pub is_dir: bool,
+    /// Size of the file itself; zero for directories.
+    pub size: u64,
+}
+
+impl FileSystemCapabilities {
+    /// Moves `path` into the trash of the allowed root containing it, or of
+    /// its parent directory when the filesystem is unrestricted. Returns the
+    /// entry's record and where the entry now lives.
+    pub(super) async fn move_to_trash(&self, path: &Path) -> Result<(TrashEntry, PathBuf)> {
+        let trash_dir = self.trash_dir_for(path.parent().unwrap_or(Path::new("/")));
+        tokio::fs::create_dir_all(&trash_dir).await?;
+        let metadata = tokio::fs::symlink_metadata(path).await?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let stamp = now.as_millis();
+        let mut id = stamp.to_string();
+        let mut attempt = 1;
+        // `create_dir` fails on an existing directory, which makes claiming
+        // an id atomic even with concurrent deletes.
+        loop {
+            match tokio::fs::create_dir(trash_dir.join(&id)).await {
+                Ok(()) => break,
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                    id = format!("{stamp}-{attempt}");
+                    attempt += 1;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let entry = TrashEntry {
+            id,
+            original_path: path.to_path_buf(),
+            deleted_at: now.as_secs(),
+            is_dir: metadata.is_dir(),
+            size: if metadata.is_dir() { 0 } else { metadata.len() },
+        };
+        let entry_dir = trash_dir.join(&entry.id);
+        let record = serde_json::to_vec_pretty(&entry).map_err(std::io::Error::other)?;
+        tokio::fs::write(entry_dir.join(METADATA), record).await?;
+        let item = entry_dir.join(ITEM);
+        if let Err(err) = tokio::fs::rename(path, &item).await {
+            let _ = tokio::fs::remove_dir_all(&entry_dir).await;
+            return Err(err.into());
+        }
+        Ok((entry, item))
+    }
+
+    /// Lists trashed entries, newest first. With `base` only the trash that
+    /// deletions under `base` go to is listed; otherwise that of every
+    /// allowed root.
+    pub async fn list_trash(&self, base: Option<&Path>) -> Result<Vec<TrashEntry>> {
+        let mut entries = Vec::new();
+        for trash_dir in self.trash_dirs(base).await? {
+            entries.extend(self.list_trash_dir(&trash_dir).await?);
+        }
+        entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at).then(b.id.cmp(&a.id)));
+        Ok(entries)
+    }
+
+    /// Moves a trashed entry back to its original location, or to `to`.
+    /// Fails if the destination is already taken; missing parent
+    /// directories are recreated.
+    pub async fn restore_from_trash(
+        &self,
+        base: Option<&Path>,
+        id: &str,
+        to: Option<&Path>,
+    ) -> Result<FileInfo> {
+        let (entry_dir, entry) = self.find_in_trash(base, id).await?;
+        let target = to.unwrap_or(&entry.original_path);
+        self.create_missing_parents(target).await?;
+        let target = self.resolve_new(target).await?;
+        if tokio::fs::symlink_metadata(&target).await.is_ok() {
+            return Err(CapabilityError::AlreadyExists(target));
+        }
+
+        tokio::fs::rename(entry_dir.join(ITEM), &target).await?;
+        tokio::fs::remove_dir_all(&entry_dir).await?;
+        FileInfo::load(target).await
+    }
+
+    /// Permanently removes one trashed entry, or with no `id` empties the
+    /// trash. Returns the entries removed.
+    pub async fn purge_trash(
+        &self,
+        base: Option<&Path>,
+        id: Option<&str>,
+    ) -> Result<Vec<TrashEntry>> {
+        let entries = match id {
+            Some(id) => vec![self.find_in_trash(base, id).await?],
+            None => {
+                let mut entries = Vec::new();
+                for trash_dir in self.trash_dirs(base).await? {
+                    for entry in self.list_trash_dir(&trash_dir).await? {
+                        entries.push((trash_dir.join(&entry.id), entry));
+                    }
+                }
+                entries
+            }
+        };
+
+        let mut purged = Vec::with_capacity(entries.len());
+        for (entry_dir, entry) in entries {
+            tokio::fs::remove_dir_all(&entry_dir).await?;
+            purged.push(entry);
+        }
+        Ok(purged)
+    }
+
+    async fn list_trash_dir(&self, trash_dir: &Path) -> Result<Vec<TrashEntry>> {
+        let mut entries = Vec::new();
+        let Ok(mut dir) = tokio::fs::read_dir(trash_dir).await else {
+            return Ok(entries);
+        };
+        while let Some(child) = dir.next_entry().await? {
+            if let Some(entry) = read_entry(&child.path()).await {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    async fn find_in_trash(&self, base: Option<&Path>, id: &str) -> Result<(PathBuf, TrashEntry)> {
+        if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+            return Err(CapabilityError::InvalidArgument(format!(
+                "invalid trash id: {id}"
+            )));
+        }
+        for trash_dir in self.trash_dirs(base).await? {
+            let entry_dir = trash_dir.join(id);
+            if let Some(entry) = read_entry(&entry_dir).await {
+                return Ok((entry_dir, entry));
+            }
+        }
+        Err(CapabilityError::NotFound(PathBuf::from(TRASH_DIR).join(id)))
+    }
+
+    /// Trash directories to consult for `base`, or for every allowed root.
+    async fn trash_dirs(&self, base: Option<&Path>) -> Result<Vec<PathBuf>> {
+        match base {
+            Some(base) => {
+                let base = self.resolve(base).await?;
+                Ok(vec![self.trash_dir_for(&base)])
+            }
+            None if self.roots.is_empty() => Err(CapabilityError::InvalidArgument(
+                "a path is required when no allowed roots are configured".into(),
+            )),
+            None => Ok(self.roots.iter().map(|root| root.join(TRASH_DIR)).collect()),
+        }
+    }
+
+    /// The trash used for entries deleted from directory `dir`.
+    fn trash_dir_for(&self, dir: &Path) -> PathBuf {
+        self.root_of(dir).unwrap_or(dir).join(TRASH_DIR)
+    }
+}
+
+/// Reads the record of the trash entry in `entry_dir`, skipping anything
+/// that is not a well-formed entry.
+async fn read_entry(entry_dir: &Path) -> Option<TrashEntry> {
+    let record = tokio::fs::read(entry_dir.join(METADATA)).await.ok()?;
+    let entry: TrashEntry = serde_json::from_slice(&record).ok()?;
+    let id_matches = entry_dir
+        .file_name()
+        .is_some_and(|name| name == entry.id.as_str());
+    let has_item = tokio::fs::symlink_metadata(entry_dir.join(ITEM))
+        .await
+        .is_ok();
+    (id_matches && has_item).then_some(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::fixture;
+    use super::*;
+
+    #[tokio::test]
+    async fn trash_round_trip_restores_and_purges() {
+        let f = fixture();
+        let file = f.root.join("notes.txt");
+        std::fs::write(&file, "keep me").unwrap();
+
+        let outcome = f.fs.delete(&file, false, true).await.unwrap();
+        let id = outcome.trash_id.unwrap();
+        assert!(!file.exists());
+        assert!(
+            outcome
+                .trashed_to
+                .unwrap()
+                .starts_with(f.root.join(TRASH_DIR))
+        );
+
+        let entries = f.fs.list_trash(None).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, id);
+        assert_eq!(entries[0].original_path, file);
+        assert_eq!(entries[0].size, 7);
+
+        f.fs.restore_from_trash(None, &id, None).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "keep me");
+        assert!(f.fs.list_trash(None).await.unwrap().is_empty());
+
+        let id =
+            f.fs.delete(&file, false, true)
+                .await
+                .unwrap()
+                .trash_id
+                .unwrap();
+        let purged = f.fs.purge_trash(None, Some(&id)).await.unwrap();
+        assert_eq!(purged.len(), 1);
+        assert!(f.fs.list_trash(None).await.unwrap().is_empty());
+        assert!(!f.root.join(TRASH_DIR).join(&id).exists());
+    }
+
+    #[tokio::test]
+    async fn restore_refuses_to_overwrite() {
+        let f = fixture();
+        let file = f.root.join("a.txt");
+        std::fs::write(&file, "old").unwrap();
+        let id =
+            f.fs.delete(&file, false, true)
+                .await
+                .unwrap()
+                .trash_id
+                .unwrap();
+        std::fs::write(&file, "new").unwrap();
+
+        let err = f.fs.restore_from_trash(None, &id, None).await.unwrap_err();
+        assert!(matches!(err, CapabilityError::AlreadyExists(_)));
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "new");
+        assert_eq!(f.fs.list_trash(None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn restore_recreates_missing_parents_and_directories() {
+        let f = fixture();
+        let dir = f.root.join("a/b");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("c.txt"), "c").unwrap();
+
+        let id =
+            f.fs.delete(&dir, true, true)
+                .await
+                .unwrap()
+                .trash_id
+                .unwrap();
+        std::fs::remove_dir(f.root.join("a")).unwrap();
+
+        f.fs.restore_from_trash(None, &id, None).await.unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("c.txt")).unwrap(), "c");
+    }
+
+    #[tokio::test]
+    async fn trash_ids_cannot_escape_the_trash() {
+        let f = fixture();
+        for id in ["../root", "a/b", ".hidden", ""] {
+            let err = f.fs.restore_from_trash(None, id, None).await.unwrap_err();
+            assert!(matches!(err, CapabilityError::InvalidArgument(_)), "{id}");
+        }
+    }
+}
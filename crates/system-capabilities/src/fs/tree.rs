@@ -0,0 +1,185 @@
+use std::collections::VecDeque;
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::{FileInfo, FileSystemCapabilities};
+use crate::error::{CapabilityError, Result};
+
+/// A node of a directory tree returned by
+/// [`FileSystemCapabilities::read_tree`].
+#[derive(Debug, Clone, Serialize)]
+pub struct FileSystemEntry {
+    #[serde(flatten)]
+    pub info: FileInfo,
+    /// Children of a directory, or `None` when the directory was not
+    /// expanded because it lies at the depth limit.
+    pub children: Option<Vec<FileSystemEntry>>,
+    /// Set on directories whose listing was cut short by the entry budget.
+    pub truncated: bool,
+}
+
+impl FileSystemCapabilities {
+    /// Lists `path` and its subdirectories down to `max_depth` levels,
+    /// returning at most `max_entries` entries overall. Levels are filled
+    /// breadth-first so the budget goes to the shallowest entries.
+    pub async fn read_tree(
+        &self,
+        path: &Path,
+        max_depth: usize,
+        max_entries: usize,
+    ) -> Result<FileSystemEntry> {
+        let path = self.resolve(path).await?;
+        let metadata = tokio::fs::metadata(&path).await?;
+        if !metadata.is_dir() {
+            return Err(CapabilityError::InvalidArgument(format!(
+                "not a directory: {}",
+                path.display()
+            )));
+        }
+
+        let root = FileInfo::from_metadata(path, &metadata);
+        tokio::task::spawn_blocking(move || build_tree(root, max_depth, max_entries))
+            .await
+            .map_err(|err| CapabilityError::Io(std::io::Error::other(err)))
+    }
+}
+
+struct Node {
+    info: FileInfo,
+    children: Option<Vec<usize>>,
+    truncated: bool,
+}
+
+fn build_tree(root: FileInfo, max_depth: usize, max_entries: usize) -> FileSystemEntry {
+    let mut nodes = vec![Node {
+        info: root,
+        children: None,
+        truncated: false,
+    }];
+    let mut budget = max_entries;
+    let mut queue = VecDeque::from([(0usize, 0usize)]);
+
+    while let Some((index, depth)) = queue.pop_front() {
+        if depth >= max_depth {
+            continue;
+        }
+
+        let mut children = Vec::new();
+        let entries = list_sorted(&nodes[index].info.path);
+        if entries.len() > budget {
+            nodes[index].truncated = true;
+        }
+        for info in entries.into_iter().take(budget) {
+            let info = info.sniff_blocking();
+            let child = nodes.len();
+            if info.is_dir {
+                queue.push_back((child, depth + 1));
+            }
+            nodes.push(Node {
+                info,
+                children: None,
+                truncated: false,
+            });
+            children.push(child);
+        }
+        budget -= children.len();
+        nodes[index].children = Some(children);
+    }
+
+    assemble(&mut nodes, 0)
+}
+
+/// Directories first, then by name, without content detection. Unreadable
+/// entries are skipped.
+fn list_sorted(dir: &Path) -> Vec<FileInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut infos: Vec<FileInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some(FileInfo::from_metadata(entry.path(), &metadata))
+        })
+        .collect();
+    infos.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    infos
+}
+
+fn assemble(nodes: &mut [Node], index: usize) -> FileSystemEntry {
+    let children = nodes[index].children.take().map(|children| {
+        children
+            .into_iter()
+            .map(|child| assemble(nodes, child))
+            .collect()
+    });
+    let node = &nodes[index];
+    FileSystemEntry {
+        info: node.info.clone(),
+        children,
+        truncated: node.truncated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::fixture;
+    use super::*;
+
+    fn names(entry: &FileSystemEntry) -> Vec<&str> {
+        entry
+            .children
+            .as_ref()
+            .map(|children| {
+                children
+                    .iter()
+                    .map(|child| child.info.name.as_str())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn read_tree_lists_directories_first_down_to_the_depth_limit() {
+        let f = fixture();
+        std::fs::create_dir_all(f.root.join("b/inner")).unwrap();
+        std::fs::write(f.root.join("a.txt"), "").unwrap();
+        std::fs::write(f.root.join("b/c.txt"), "").unwrap();
+
+        let tree = f.fs.read_tree(&f.root, 2, 100).await.unwrap();
+        assert_eq!(names(&tree), ["b", "a.txt"]);
+        let b = &tree.children.as_ref().unwrap()[0];
+        assert_eq!(names(b), ["inner", "c.txt"]);
+        // At the depth limit: not expanded.
+        assert!(b.children.as_ref().unwrap()[0].children.is_none());
+    }
+
+    #[tokio::test]
+    async fn read_tree_spends_the_entry_budget_breadth_first() {
+        let f = fixture();
+        std::fs::create_dir(f.root.join("dir")).unwrap();
+        std::fs::write(f.root.join("dir/deep.txt"), "").unwrap();
+        for i in 0..3 {
+            std::fs::write(f.root.join(format!("file{i}")), "").unwrap();
+        }
+
+        let tree = f.fs.read_tree(&f.root, 5, 3).await.unwrap();
+        assert!(tree.truncated);
+        assert_eq!(names(&tree), ["dir", "file0", "file1"]);
+        let dir = &tree.children.as_ref().unwrap()[0];
+        assert!(dir.truncated);
+        assert!(names(dir).is_empty());
+    }
+
+    #[tokio::test]
+    async fn read_tree_rejects_files() {
+        let f = fixture();
+        std::fs::write(f.root.join("a.txt"), "").unwrap();
+        let err =
+            f.fs.read_tree(&f.root.join("a.txt"), 1, 10)
+                .await
+                .unwrap_err();
+        assert!(matches!(err, CapabilityError::InvalidArgument(_)));
+    }
+}
@@ -0,0 +1,178 @@
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+use super::{FileInfo, FileSystemCapabilities, map_not_found};
+use crate::error::{CapabilityError, Result};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WriteOptions {
+    /// Replace the file if it already exists.
+    pub overwrite: bool,
+    /// Create missing parent directories.
+    pub create_parents: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeleteOutcome {
+    pub path: PathBuf,
+    /// Where the entry was moved to when deleted into the trash.
+    pub trashed_to: Option<PathBuf>,
+    /// Id to restore the entry by when deleted into the trash.
+    pub trash_id: Option<String>,
+}
+
+impl FileSystemCapabilities {
+    pub async fn write_file(
+        &self,
+        path: &Path,
+        contents: &[u8],
+        options: WriteOptions,
+    ) -> Result<FileInfo> {
+        if contents.len() as u64 > self.config.max_write_bytes {
+            return Err(CapabilityError::TooLarge {
+                limit: self.config.max_write_bytes,
+            });
+        }
+
+        if options.create_parents {
+            self.create_missing_parents(path).await?;
+        }
+        let path = self.resolve_new(path).await?;
+
+        match tokio::fs::symlink_metadata(&path).await {
+            Ok(metadata) if metadata.is_dir() => return Err(CapabilityError::NotAFile(path)),
+            Ok(metadata) if metadata.is_symlink() => {
+                return Err(CapabilityError::InvalidArgument(format!(
+                    "refusing to write through a symlink: {}",
+                    path.display()
+                )));
+            }
+            Ok(_) if !options.overwrite => return Err(CapabilityError::AlreadyExists(path)),
+            _ => {}
+        }
+
+        // The checks above race with concurrent changes; `create_new` and
+        // `O_NOFOLLOW` make the open itself refuse a file or link that
+        // appeared in the meantime.
+        let mut open = tokio::fs::OpenOptions::new();
+        open.write(true);
+        if options.overwrite {
+            open.create(true).truncate(true);
+        } else {
+            open.create_new(true);
+        }
+        #[cfg(unix)]
+        open.custom_flags(libc::O_NOFOLLOW);
+        let mut file = open.open(&path).await.map_err(|err| match err.kind() {
+            ErrorKind::AlreadyExists => CapabilityError::AlreadyExists(path.clone()),
+            #[cfg(unix)]
+            _ if err.raw_os_error() == Some(libc::ELOOP) => CapabilityError::InvalidArgument(
+                format!("refusing to write through a symlink: {}", path.display()),
+            ),
+            _ => CapabilityError::Io(err),
+        })?;
+        file.write_all(contents).await?;
+        file.flush().await?;
+        drop(file);
+        FileInfo::load(path).await
+    }
+
+    pub async fn create_directory(&self, path: &Path, recursive: bool) -> Result<FileInfo> {
+        if recursive {
+            self.create_missing_parents(path).await?;
+        }
+        let path = self.resolve_new(path).await?;
+
+        match tokio::fs::create_dir(&path).await {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                let is_dir = tokio::fs::metadata(&path).await?.is_dir();
+                if !(recursive && is_dir) {
+                    return Err(CapabilityError::AlreadyExists(path));
+                }
+            }
+            Err(err) => return Err(err.into()),
+        }
+        FileInfo::load(path).await
+    }
+
+    pub async fn rename(&self, from: &Path, to: &Path, overwrite: bool) -> Result<FileInfo> {
+        let from = self.resolve_mutable(from).await?;
+        let to = self.resolve_new(to).await?;
+
+        if !overwrite && tokio::fs::symlink_metadata(&to).await.is_ok() {
+            return Err(CapabilityError::AlreadyExists(to));
+        }
+
+        tokio::fs::rename(&from, &to).await?;
+        FileInfo::load(to).await
+    }
+
+    /// Deletes a file or directory. Non-empty directories require
+    /// `recursive`. With `trash` the entry is moved into the trash instead
+    /// of being removed.
+    pub async fn delete(&self, path: &Path, recursive: bool, trash: bool) -> Result<DeleteOutcome> {
+        let path = self.resolve_mutable(path).await?;
+        let metadata = tokio::fs::symlink_metadata(&path).await?;
+
+        if metadata.is_dir() && !recursive && !is_empty_dir(&path).await? {
+            return Err(CapabilityError::InvalidArgument(format!(
+                "directory is not empty: {}",
+                path.display()
+            )));
+        }
+
+        if trash {
+            let (entry, trashed_to) = self.move_to_trash(&path).await?;
+            return Ok(DeleteOutcome {
+                path,
+                trashed_to: Some(trashed_to),
+                trash_id: Some(entry.id),
+            });
+        }
+
+        if metadata.is_dir() {
+            tokio::fs::remove_dir_all(&path).await?;
+        } else {
+            tokio::fs::remove_file(&path).await?;
+        }
+        Ok(DeleteOutcome {
+            path,
+            trashed_to: None,
+            trash_id: None,
+        })
+    }
+
+    /// Creates the missing ancestors of `path`, checking the deepest existing
+    /// ancestor against the allowed roots first.
+    pub(super) async fn create_missing_parents(&self, path: &Path) -> Result<()> {
+        if path.components().any(|c| matches!(c, Component::ParentDir)) {
+            return Err(CapabilityError::InvalidArgument(format!(
+                "parent directory components are not allowed: {}",
+                path.display()
+            )));
+        }
+        let Some(parent) = path.parent() else {
+            return Ok(());
+        };
+        let mut existing = parent;
+        while tokio::fs::symlink_metadata(existing).await.is_err() {
+            match existing.parent() {
+                Some(next) => existing = next,
+                None => break,
+            }
+        }
+        self.resolve(existing).await?;
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|err| map_not_found(err, parent))
+    }
+}
+
+async fn is_empty_dir(path: &Path) -> Result<bool> {
+    let mut entries = tokio::fs::read_dir(path).await?;
+    Ok(entries.next_entry().await?.is_none())
+}
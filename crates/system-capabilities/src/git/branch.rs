@@ -0,0 +1,76 @@
+use serde::Serialize;
+
+use super::GitRepository;
+use crate::error::{CapabilityError, Result};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchInfo {
+    pub name: String,
+    pub current: bool,
+}
+
+impl GitRepository {
+    /// Local branches.
+    pub async fn branches(&self) -> Result<Vec<BranchInfo>> {
+        let stdout = self
+            .run(["branch", "--format=%(HEAD)%(refname:short)"])
+            .await?;
+        Ok(String::from_utf8_lossy(&stdout)
+            .lines()
+            .filter_map(|line| {
+                let (marker, name) = line.split_at_checked(1)?;
+                Some(BranchInfo {
+                    name: name.to_string(),
+                    current: marker == "*",
+                })
+            })
+            .collect())
+    }
+
+    /// Creates `name` at `start_point` (HEAD by default), switching to it
+    /// when `switch` is set.
+    pub async fn create_branch(
+        &self,
+        name: &str,
+        start_point: Option<&str>,
+        switch: bool,
+    ) -> Result<()> {
+        self.check_branch_name(name).await?;
+        if start_point.is_some_and(|start| start.starts_with('-')) {
+            return Err(CapabilityError::InvalidArgument(
+                "invalid start point".into(),
+            ));
+        }
+
+        let mut args = if switch {
+            vec!["switch", "--quiet", "-c", name]
+        } else {
+            vec!["branch", name]
+        };
+        args.extend(start_point);
+        self.run(args).await?;
+        Ok(())
+    }
+
+    /// Switches the working tree to the existing branch `name`.
+    pub async fn switch_branch(&self, name: &str) -> Result<()> {
+        self.check_branch_name(name).await?;
+        self.run(["switch", "--quiet", name]).await?;
+        Ok(())
+    }
+
+    async fn check_branch_name(&self, name: &str) -> Result<()> {
+        let valid = !name.starts_with('-')
+            && self
+                .run(["check-ref-format", "--branch", name])
+                .await
+                .is_ok();
+        if valid {
+            Ok(())
+        } else {
+            Err(CapabilityError::InvalidArgument(format!(
+                "invalid branch name: {name}"
+            )))
+        }
+    }
+}
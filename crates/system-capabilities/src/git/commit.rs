@@ -0,0 +1,70 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use super::{CommitInfo, GitRepository};
+use crate::error::{CapabilityError, Result};
+
+/// Identity recorded on a commit when the repository has none configured
+/// or the caller wants to override it.
+#[derive(Debug, Clone)]
+pub struct CommitAuthor {
+    pub name: String,
+    pub email: String,
+}
+
+impl GitRepository {
+    /// Adds `paths` (relative to the repository root) to the index.
+    pub async fn stage(&self, paths: &[PathBuf]) -> Result<()> {
+        self.run(path_args(["add"], paths)?).await?;
+        Ok(())
+    }
+
+    /// Removes `paths` from the index, keeping the working tree changes.
+    pub async fn unstage(&self, paths: &[PathBuf]) -> Result<()> {
+        self.run(path_args(["restore", "--staged"], paths)?).await?;
+        Ok(())
+    }
+
+    /// Commits the index and returns the new HEAD commit.
+    pub async fn commit(&self, message: &str, author: Option<&CommitAuthor>) -> Result<CommitInfo> {
+        if message.trim().is_empty() {
+            return Err(CapabilityError::InvalidArgument(
+                "commit message must not be empty".into(),
+            ));
+        }
+
+        let mut args: Vec<OsString> = Vec::new();
+        if let Some(author) = author {
+            args.extend([
+                "-c".into(),
+                format!("user.name={}", author.name).into(),
+                "-c".into(),
+                format!("user.email={}", author.email).into(),
+            ]);
+        }
+        args.extend([
+            "commit".into(),
+            "--quiet".into(),
+            "--no-verify".into(),
+            "-m".into(),
+            message.into(),
+        ]);
+        self.run(args).await?;
+
+        self.log(1)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| CapabilityError::Git("commit did not create a HEAD".into()))
+    }
+}
+
+fn path_args<const N: usize>(command: [&str; N], paths: &[PathBuf]) -> Result<Vec<OsString>> {
+    if paths.is_empty() {
+        return Err(CapabilityError::InvalidArgument("no paths given".into()));
+    }
+    let mut args: Vec<OsString> = command.iter().map(OsString::from).collect();
+    args.push("--".into());
+    args.extend(paths.iter().map(|path| path.as_os_str().to_owned()));
+    Ok(args)
+}
@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::GitRepository;
+use crate::error::Result;
+
+/// Largest diff returned in full; anything beyond is cut off.
+const MAX_DIFF_BYTES: usize = 2 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitDiff {
+    /// Unified diff text.
+    pub diff: String,
+    pub truncated: bool,
+}
+
+impl GitRepository {
+    /// Diff of the working tree against the index, or of the index against
+    /// HEAD when `staged`, optionally limited to `paths` (relative to the
+    /// repository root).
+    pub async fn diff(&self, staged: bool, paths: &[PathBuf]) -> Result<GitDiff> {
+        let mut args = vec![
+            "diff".into(),
+            "--no-color".into(),
+            "--no-ext-diff".into(),
+            "--no-textconv".into(),
+        ];
+        if staged {
+            args.push("--cached".into());
+        }
+        args.push("--".into());
+        args.extend(paths.iter().map(|path| path.as_os_str().to_owned()));
+
+        let mut stdout = self.run(args).await?;
+        let truncated = stdout.len() > MAX_DIFF_BYTES;
+        stdout.truncate(MAX_DIFF_BYTES);
+
+        Ok(GitDiff {
+            diff: String::from_utf8_lossy(&stdout).into_owned(),
+            truncated,
+        })
+    }
+}
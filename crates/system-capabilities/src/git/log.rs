@@ -0,0 +1,47 @@
+use serde::Serialize;
+
+use super::GitRepository;
+use crate::error::Result;
+
+const FIELD_SEP: char = '\x1f';
+const RECORD_SEP: char = '\x1e';
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitInfo {
+    pub id: String,
+    pub short_id: String,
+    pub author_name: String,
+    pub author_email: String,
+    /// Author time in seconds since the Unix epoch.
+    pub timestamp: i64,
+    pub summary: String,
+}
+
+impl GitRepository {
+    /// The `limit` most recent commits reachable from HEAD. An empty
+    /// repository has no commits.
+    pub async fn log(&self, limit: usize) -> Result<Vec<CommitInfo>> {
+        if self.head().await?.is_none() {
+            return Ok(Vec::new());
+        }
+
+        let format = "--format=%H%x1f%h%x1f%an%x1f%ae%x1f%at%x1f%s%x1e";
+        let stdout = self.run(["log", &format!("-n{limit}"), format]).await?;
+
+        let output = String::from_utf8_lossy(&stdout);
+        Ok(output
+            .split(RECORD_SEP)
+            .filter_map(|record| {
+                let mut fields = record.trim_start_matches('\n').split(FIELD_SEP);
+                Some(CommitInfo {
+                    id: fields.next().filter(|id| !id.is_empty())?.to_string(),
+                    short_id: fields.next()?.to_string(),
+                    author_name: fields.next()?.to_string(),
+                    author_email: fields.next()?.to_string(),
+                    timestamp: fields.next()?.parse().ok()?,
+                    summary: fields.next()?.to_string(),
+                })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,288 @@
+//! Git repository access through the `git` command line tool.
+//!
+//! Repositories are untrusted input: anyone with write access to a working
+//! tree can edit its `.git/config`. Every invocation therefore disables the
+//! configuration that makes git run arbitrary commands (fsmonitor, hooks,
+//! clean/smudge filters, signing and signature verification, external diff
+//! and textconv drivers, automatic maintenance). Commits made through this
+//! module skip the repository's hooks as a result. The test at the bottom
+//! of this file runs every operation against a repository configured to
+//! abuse each of these and should be extended along with [`HARDENING`].
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use serde::Serialize;
+use tokio::process::Command;
+
+use crate::error::{CapabilityError, Result};
+
+mod branch;
+mod commit;
+mod diff;
+mod log;
+mod status;
+
+pub use branch::BranchInfo;
+pub use commit::CommitAuthor;
+pub use diff::GitDiff;
+pub use log::CommitInfo;
+pub use status::{GitStatus, StatusEntry};
+
+/// A git working tree located by [`GitRepository::discover`].
+#[derive(Debug, Clone)]
+pub struct GitRepository {
+    root: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RepositoryInfo {
+    pub root: PathBuf,
+    /// Current branch, or `None` when HEAD is detached.
+    pub branch: Option<String>,
+    /// Commit id of HEAD, or `None` before the first commit.
+    pub head: Option<String>,
+}
+
+impl GitRepository {
+    /// Finds the repository containing `path`, which may be a file or a
+    /// directory.
+    pub async fn discover(path: &Path) -> Result<Self> {
+        let is_dir = tokio::fs::metadata(path)
+            .await
+            .map_err(|err| crate::fs::map_not_found(err, path))?
+            .is_dir();
+        let dir = match path.parent() {
+            Some(parent) if !is_dir => parent,
+            _ => path,
+        };
+        let output = git_output(dir, &[], ["rev-parse", "--show-toplevel"]).await?;
+        if !output.status.success() {
+            return Err(CapabilityError::NotARepository(path.to_path_buf()));
+        }
+        let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(Self {
+            root: PathBuf::from(root),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub async fn info(&self) -> Result<RepositoryInfo> {
+        let status = self.status().await?;
+        Ok(RepositoryInfo {
+            root: self.root.clone(),
+            branch: status.branch,
+            head: status.head,
+        })
+    }
+
+    /// Runs git in the repository root and returns stdout, failing with the
+    /// trimmed stderr when git exits unsuccessfully.
+    pub(crate) async fn run<I, S>(&self, args: I) -> Result<Vec<u8>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let filters = self.filter_drivers().await?;
+        let output = git_output(&self.root, &filters, args).await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(CapabilityError::Git(stderr));
+        }
+        Ok(output.stdout)
+    }
+
+    /// Commit id of HEAD, or `None` before the first commit.
+    pub(crate) async fn head(&self) -> Result<Option<String>> {
+        let output = git_output(&self.root, &[], ["rev-parse", "--verify", "-q", "HEAD"]).await?;
+        let id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok((output.status.success() && !id.is_empty()).then_some(id))
+    }
+
+    /// Names of the clean/smudge filter drivers configured for the
+    /// repository, which [`git_output`] neutralizes. Read on every call since
+    /// the config may change between calls.
+    async fn filter_drivers(&self) -> Result<Vec<String>> {
+        let output = git_output(
+            &self.root,
+            &[],
+            ["config", "--name-only", "--get-regexp", r"^filter\."],
+        )
+        .await?;
+        let names = String::from_utf8_lossy(&output.stdout);
+        let mut drivers: Vec<String> = names
+            .lines()
+            .filter_map(|key| key.strip_prefix("filter.")?.rsplit_once('.'))
+            .map(|(driver, _)| driver.to_string())
+            .collect();
+        drivers.sort();
+        drivers.dedup();
+        Ok(drivers)
+    }
+}
+
+/// Config overrides applied to every invocation; see the module docs.
+const HARDENING: &[&str] = &[
+    "core.quotepath=off",
+    "core.fsmonitor=false",
+    "core.hooksPath=/dev/null",
+    "commit.gpgsign=false",
+    "tag.gpgsign=false",
+    "log.showSignature=false",
+    "gpg.program=/dev/null",
+    "gpg.openpgp.program=/dev/null",
+    "gpg.ssh.program=/dev/null",
+    "gpg.x509.program=/dev/null",
+    "gc.auto=0",
+    "maintenance.auto=false",
+];
+
+/// Runs git in `cwd` with the [`HARDENING`] overrides, and with the given
+/// filter drivers emptied out.
+async fn git_output<I, S>(
+    cwd: &Path,
+    filter_drivers: &[String],
+    args: I,
+) -> Result<std::process::Output>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let mut command = Command::new("git");
+    for setting in HARDENING {
+        command.arg("-c").arg(setting);
+    }
+    for driver in filter_drivers {
+        for key in ["clean", "smudge", "process"] {
+            command.arg("-c").arg(format!("filter.{driver}.{key}="));
+        }
+    }
+    command
+        .arg("--no-optional-locks")
+        .args(args)
+        .current_dir(cwd)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("LC_ALL", "C")
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => CapabilityError::Git("git executable not found".into()),
+            _ => CapabilityError::Io(err),
+        })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+    use std::process::Command as StdCommand;
+
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = StdCommand::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    /// A repository whose head commit carries a signature and whose config
+    /// points every command hook git knows of at a script that leaves a
+    /// marker file behind.
+    fn hostile_repository(dir: &Path) -> PathBuf {
+        let marker = dir.join("ran");
+        let script = dir.join("evil.sh");
+        std::fs::write(
+            &script,
+            format!("#!/bin/sh\necho \"$0 $*\" >> '{}'\ncat\n", marker.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let hooks = dir.join("hooks");
+        std::fs::create_dir(&hooks).unwrap();
+        for hook in ["pre-commit", "commit-msg", "post-commit", "post-checkout"] {
+            std::os::unix::fs::symlink(&script, hooks.join(hook)).unwrap();
+        }
+
+        let repo = dir.join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]);
+        git(&repo, &["config", "user.name", "Test"]);
+        git(&repo, &["config", "user.email", "test@example.com"]);
+        std::fs::write(repo.join("a.txt"), "one\n").unwrap();
+        std::fs::write(repo.join(".gitattributes"), "*.txt filter=evil diff=evil\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-q", "-m", "initial"]);
+
+        let tree = git(&repo, &["rev-parse", "HEAD^{tree}"]);
+        let parent = git(&repo, &["rev-parse", "HEAD"]);
+        let signed = format!(
+            "tree {tree}\nparent {parent}\n\
+             author Test <test@example.com> 1700000000 +0000\n\
+             committer Test <test@example.com> 1700000000 +0000\n\
+             gpgsig -----BEGIN PGP SIGNATURE-----\n \n iQEzBAABCAAdFiEE\n -----END PGP SIGNATURE-----\n\
+             \nsigned\n"
+        );
+        let signed_path = dir.join("signed-commit");
+        std::fs::write(&signed_path, signed).unwrap();
+        let id = git(
+            &repo,
+            &[
+                "hash-object",
+                "-t",
+                "commit",
+                "-w",
+                signed_path.to_str().unwrap(),
+            ],
+        );
+        git(&repo, &["update-ref", "refs/heads/main", &id]);
+
+        let script = script.to_str().unwrap();
+        for (key, value) in [
+            ("core.fsmonitor", script),
+            ("core.hooksPath", hooks.to_str().unwrap()),
+            ("filter.evil.clean", script),
+            ("filter.evil.smudge", script),
+            ("diff.evil.textconv", script),
+            ("diff.external", script),
+            ("commit.gpgsign", "true"),
+            ("log.showSignature", "true"),
+            ("gpg.program", script),
+            ("gpg.openpgp.program", script),
+            ("gpg.ssh.program", script),
+            ("gpg.x509.program", script),
+        ] {
+            git(&repo, &["config", key, value]);
+        }
+        std::fs::write(repo.join("a.txt"), "two\n").unwrap();
+        repo
+    }
+
+    #[tokio::test]
+    async fn repository_config_cannot_run_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = hostile_repository(dir.path());
+
+        let repository = GitRepository::discover(&repo.join("a.txt")).await.unwrap();
+        repository.info().await.unwrap();
+        repository.status().await.unwrap();
+        assert_eq!(repository.log(10).await.unwrap().len(), 2);
+        repository.diff(false, &[]).await.unwrap();
+        repository.stage(&[PathBuf::from("a.txt")]).await.unwrap();
+        repository.diff(true, &[]).await.unwrap();
+        repository.commit("change", None).await.unwrap();
+        repository.create_branch("topic", None, true).await.unwrap();
+        repository.switch_branch("main").await.unwrap();
+
+        let ran = std::fs::read_to_string(dir.path().join("ran")).unwrap_or_default();
+        assert!(ran.is_empty(), "repository config ran commands:\n{ran}");
+    }
+}
@@ -0,0 +1,198 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::GitRepository;
+use crate::error::Result;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitStatus {
+    pub branch: Option<String>,
+    pub head: Option<String>,
+    pub upstream: Option<String>,
+    /// Commits ahead of / behind the upstream, when one is configured.
+    pub ahead: u32,
+    pub behind: u32,
+    /// Whether there are staged, unstaged or untracked changes.
+    pub dirty: bool,
+    pub entries: Vec<StatusEntry>,
+}
+
+/// A changed path, with git's one-letter status codes for the index and
+/// the working tree (`.` for unchanged, `?` for untracked).
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusEntry {
+    pub path: PathBuf,
+    /// Source path of a rename or copy.
+    pub original_path: Option<PathBuf>,
+    pub index_status: char,
+    pub worktree_status: char,
+    pub conflicted: bool,
+}
+
+impl GitRepository {
+    pub async fn status(&self) -> Result<GitStatus> {
+        let stdout = self
+            .run(["status", "--porcelain=v2", "--branch", "-z"])
+            .await?;
+        Ok(parse_status(&String::from_utf8_lossy(&stdout)))
+    }
+}
+
+/// Parses `git status --porcelain=v2 --branch -z`.
+fn parse_status(output: &str) -> GitStatus {
+    let mut status = GitStatus {
+        branch: None,
+        head: None,
+        upstream: None,
+        ahead: 0,
+        behind: 0,
+        dirty: false,
+        entries: Vec::new(),
+    };
+
+    let mut records = output.split('\0').filter(|record| !record.is_empty());
+    while let Some(record) = records.next() {
+        if let Some(header) = record.strip_prefix("# ") {
+            parse_header(&mut status, header);
+            continue;
+        }
+
+        let (kind, rest) = record.split_at(1);
+        let entry = match kind {
+            // 1 XY sub mH mI mW hH hI path
+            "1" => {
+                let fields: Vec<_> = rest.trim_start().splitn(8, ' ').collect();
+                entry(fields.first(), fields.get(7), None, false)
+            }
+            // 2 XY sub mH mI mW hH hI Xscore path, followed by the original path
+            "2" => {
+                let fields: Vec<_> = rest.trim_start().splitn(9, ' ').collect();
+                let original = records.next();
+                entry(fields.first(), fields.get(8), original, false)
+            }
+            // u XY sub m1 m2 m3 mW h1 h2 h3 path
+            "u" => {
+                let fields: Vec<_> = rest.trim_start().splitn(10, ' ').collect();
+                entry(fields.first(), fields.get(9), None, true)
+            }
+            "?" => Some(StatusEntry {
+                path: PathBuf::from(rest.trim_start()),
+                original_path: None,
+                index_status: '?',
+                worktree_status: '?',
+                conflicted: false,
+            }),
+            _ => None,
+        };
+        status.entries.extend(entry);
+    }
+
+    status.dirty = !status.entries.is_empty();
+    status
+}
+
+fn parse_header(status: &mut GitStatus, header: &str) {
+    let Some((key, value)) = header.split_once(' ') else {
+        return;
+    };
+    match key {
+        "branch.oid" if value != "(initial)" => status.head = Some(value.to_string()),
+        "branch.head" if value != "(detached)" => status.branch = Some(value.to_string()),
+        "branch.upstream" => status.upstream = Some(value.to_string()),
+        "branch.ab" => {
+            for part in value.split(' ') {
+                if let Some(ahead) = part.strip_prefix('+') {
+                    status.ahead = ahead.parse().unwrap_or(0);
+                } else if let Some(behind) = part.strip_prefix('-') {
+                    status.behind = behind.parse().unwrap_or(0);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn entry(
+    xy: Option<&&str>,
+    path: Option<&&str>,
+    original: Option<&str>,
+    conflicted: bool,
+) -> Option<StatusEntry> {
+    let mut codes = xy?.chars();
+    Some(StatusEntry {
+        path: PathBuf::from(path?),
+        original_path: original.map(PathBuf::from),
+        index_status: codes.next()?,
+        worktree_status: codes.next()?,
+        conflicted,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_status_reads_branch_headers() {
+        let output = "# branch.oid 0123abcd\0# branch.head main\0\
+                      # branch.upstream origin/main\0# branch.ab +2 -1\0";
+        let status = parse_status(output);
+        assert_eq!(status.head.as_deref(), Some("0123abcd"));
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!((status.ahead, status.behind), (2, 1));
+        assert!(!status.dirty);
+    }
+
+    #[test]
+    fn parse_status_handles_an_empty_detached_repository() {
+        let status = parse_status("# branch.oid (initial)\0# branch.head (detached)\0");
+        assert_eq!(status.head, None);
+        assert_eq!(status.branch, None);
+    }
+
+    #[test]
+    fn parse_status_reads_renames_with_their_original_path() {
+        let output = "2 R. N... 100644 100644 100644 aaaa bbbb R100 new name.txt\0old name.txt\0\
+                      ? untracked.txt\0";
+        let status = parse_status(output);
+        assert!(status.dirty);
+        assert_eq!(status.entries.len(), 2);
+
+        let renamed = &status.entries[0];
+        assert_eq!(renamed.path, PathBuf::from("new name.txt"));
+        assert_eq!(renamed.original_path, Some(PathBuf::from("old name.txt")));
+        assert_eq!((renamed.index_status, renamed.worktree_status), ('R', '.'));
+        assert!(!renamed.conflicted);
+
+        // The original path record must not be mistaken for an entry.
+        let untracked = &status.entries[1];
+        assert_eq!(untracked.path, PathBuf::from("untracked.txt"));
+        assert_eq!(untracked.index_status, '?');
+    }
+
+    #[test]
+    fn parse_status_marks_unmerged_entries_as_conflicted() {
+        let output = "u UU N... 100644 100644 100644 100644 aaaa bbbb cccc both edited.txt\0\
+                      1 .M N... 100644 100644 100644 aaaa aaaa changed.txt\0";
+        let status = parse_status(output);
+        assert_eq!(status.entries.len(), 2);
+
+        let unmerged = &status.entries[0];
+        assert_eq!(unmerged.path, PathBuf::from("both edited.txt"));
+        assert_eq!(
+            (unmerged.index_status, unmerged.worktree_status),
+            ('U', 'U')
+        );
+        assert!(unmerged.conflicted);
+
+        let modified = &status.entries[1];
+        assert_eq!(modified.path, PathBuf::from("changed.txt"));
+        assert_eq!(
+            (modified.index_status, modified.worktree_status),
+            ('.', 'M')
+        );
+        assert!(!modified.conflicted);
+    }
+}
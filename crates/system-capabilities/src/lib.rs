@@ -0,0 +1,18 @@
+//! Host capabilities exposed to the server: filesystem access scoped to a
+//! set of allowed roots, git repository access, process and system
+//! queries, and interactive terminals.
+
+pub mod error;
+pub mod fs;
+pub mod git;
+pub mod process;
+pub mod system_info;
+pub mod terminal;
+pub mod watch;
+
+pub use error::{CapabilityError, Result};
+pub use fs::{FileSystemCapabilities, FileSystemConfig};
+pub use git::GitRepository;
+pub use process::ProcessMonitor;
+pub use terminal::{Terminal, TerminalConfig, TerminalManager};
+pub use watch::{FileSystemChanged, FileWatcher};
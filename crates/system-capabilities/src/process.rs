@@ -0,0 +1,170 @@
+//! Inspection and termination of processes spawned by the server.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, Signal, System, UpdateKind};
+
+use crate::error::{CapabilityError, Result};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub parent_pid: Option<u32>,
+    pub name: String,
+    pub command: Vec<String>,
+    /// CPU usage in percent of one core since the previous sample.
+    pub cpu_usage: f32,
+    pub memory_bytes: u64,
+    pub status: String,
+    /// Start time in seconds since the Unix epoch.
+    pub started_at: u64,
+}
+
+/// Tracks the processes descended from the server process. Only those can
+/// be listed or killed, so the API cannot reach unrelated processes on the
+/// host.
+#[derive(Clone)]
+pub struct ProcessMonitor {
+    root: Pid,
+    state: Arc<Mutex<MonitorState>>,
+}
+
+struct MonitorState {
+    system: System,
+    primed: bool,
+}
+
+impl Default for ProcessMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessMonitor {
+    pub fn new() -> Self {
+        Self {
+            root: Pid::from_u32(std::process::id()),
+            state: Arc::new(Mutex::new(MonitorState {
+                system: System::new(),
+                primed: false,
+            })),
+        }
+    }
+
+    /// Descendants of the server process with their resource usage.
+    pub async fn list(&self) -> Result<Vec<ProcessInfo>> {
+        let monitor = self.clone();
+        run_blocking(move || {
+            let mut state = monitor.state.lock().unwrap();
+            state.refresh();
+            let system = &state.system;
+
+            let mut processes: Vec<ProcessInfo> = monitor
+                .descendants(system)
+                .into_iter()
+                .filter_map(|pid| system.process(pid).map(|process| (pid, process)))
+                .map(|(pid, process)| ProcessInfo {
+                    pid: pid.as_u32(),
+                    parent_pid: process.parent().map(Pid::as_u32),
+                    name: process.name().to_string_lossy().into_owned(),
+                    command: process
+                        .cmd()
+                        .iter()
+                        .map(|arg| arg.to_string_lossy().into_owned())
+                        .collect(),
+                    cpu_usage: process.cpu_usage(),
+                    memory_bytes: process.memory(),
+                    status: process.status().to_string(),
+                    started_at: process.start_time(),
+                })
+                .collect();
+            processes.sort_by_key(|process| process.pid);
+            Ok(processes)
+        })
+        .await
+    }
+
+    /// Sends SIGTERM, or SIGKILL when `force`, to a descendant of the server.
+    pub async fn kill(&self, pid: u32, force: bool) -> Result<()> {
+        let monitor = self.clone();
+        run_blocking(move || {
+            let mut state = monitor.state.lock().unwrap();
+            state.refresh();
+
+            let pid = Pid::from_u32(pid);
+            let Some(process) = state.system.process(pid) else {
+                return Err(CapabilityError::ProcessNotFound(pid.as_u32()));
+            };
+            if !monitor.descendants(&state.system).contains(&pid) {
+                return Err(CapabilityError::ProcessNotOwned(pid.as_u32()));
+            }
+
+            let signal = if force { Signal::Kill } else { Signal::Term };
+            let sent = process.kill_with(signal).unwrap_or_else(|| process.kill());
+            if sent {
+                tracing::info!(pid = pid.as_u32(), ?signal, "signalled process");
+                Ok(())
+            } else {
+                Err(CapabilityError::Io(std::io::Error::other(format!(
+                    "failed to signal process {pid}"
+                ))))
+            }
+        })
+        .await
+    }
+
+    fn descendants(&self, system: &System) -> HashSet<Pid> {
+        let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+        for (pid, process) in system.processes() {
+            // On Linux, threads are reported as tasks of their process.
+            if process.thread_kind().is_some() {
+                continue;
+            }
+            if let Some(parent) = process.parent() {
+                children.entry(parent).or_default().push(*pid);
+            }
+        }
+
+        let mut found = HashSet::new();
+        let mut stack = vec![self.root];
+        while let Some(pid) = stack.pop() {
+            for child in children.get(&pid).into_iter().flatten() {
+                if found.insert(*child) {
+                    stack.push(*child);
+                }
+            }
+        }
+        found
+    }
+}
+
+impl MonitorState {
+    /// Refreshes every process. CPU usage is measured between two samples,
+    /// so the very first refresh takes a second sample after a short pause.
+    fn refresh(&mut self) {
+        let kind = ProcessRefreshKind::nothing()
+            .with_cpu()
+            .with_memory()
+            .with_cmd(UpdateKind::OnlyIfNotSet);
+        self.system
+            .refresh_processes_specifics(ProcessesToUpdate::All, true, kind);
+        if !self.primed {
+            self.primed = true;
+            std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+            self.system
+                .refresh_processes_specifics(ProcessesToUpdate::All, true, kind);
+        }
+    }
+}
+
+async fn run_blocking<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|err| CapabilityError::Io(std::io::Error::other(err)))?
+}
@@ -0,0 +1,102 @@
+//! Host resource information.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sysinfo::{Disks, MemoryRefreshKind, System};
+
+use crate::error::{CapabilityError, Result};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemInfo {
+    pub os: OsInfo,
+    pub cpu: CpuInfo,
+    pub memory: MemoryInfo,
+    /// Free space on the disk holding each root.
+    pub disks: Vec<RootDiskInfo>,
+    /// Seconds since the host booted.
+    pub uptime_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OsInfo {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub kernel_version: Option<String>,
+    pub arch: String,
+    pub host_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CpuInfo {
+    pub logical_cores: usize,
+    pub physical_cores: Option<usize>,
+    /// 1, 5 and 15 minute load averages; zero where unsupported.
+    pub load_average: [f64; 3],
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryInfo {
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RootDiskInfo {
+    pub root: PathBuf,
+    pub mount_point: Option<PathBuf>,
+    pub total_bytes: Option<u64>,
+    pub available_bytes: Option<u64>,
+}
+
+/// Collects a snapshot of host resources, reporting disk space for each of
+/// `roots`.
+pub async fn collect(roots: Vec<PathBuf>) -> Result<SystemInfo> {
+    tokio::task::spawn_blocking(move || collect_blocking(&roots))
+        .await
+        .map_err(|err| CapabilityError::Io(std::io::Error::other(err)))
+}
+
+fn collect_blocking(roots: &[PathBuf]) -> SystemInfo {
+    let mut system = System::new();
+    system.refresh_memory_specifics(MemoryRefreshKind::nothing().with_ram());
+    let load = System::load_average();
+    let disks = Disks::new_with_refreshed_list();
+
+    SystemInfo {
+        os: OsInfo {
+            name: System::name(),
+            version: System::long_os_version(),
+            kernel_version: System::kernel_version(),
+            arch: System::cpu_arch(),
+            host_name: System::host_name(),
+        },
+        cpu: CpuInfo {
+            logical_cores: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            physical_cores: System::physical_core_count(),
+            load_average: [load.one, load.five, load.fifteen],
+        },
+        memory: MemoryInfo {
+            total_bytes: system.total_memory(),
+            available_bytes: system.available_memory(),
+        },
+        disks: roots.iter().map(|root| disk_for(&disks, root)).collect(),
+        uptime_secs: System::uptime(),
+    }
+}
+
+/// Picks the disk with the longest mount point containing `root`.
+fn disk_for(disks: &Disks, root: &Path) -> RootDiskInfo {
+    let disk = disks
+        .list()
+        .iter()
+        .filter(|disk| root.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().components().count());
+
+    RootDiskInfo {
+        root: root.to_path_buf(),
+        mount_point: disk.map(|disk| disk.mount_point().to_path_buf()),
+        total_bytes: disk.map(|disk| disk.total_space()),
+        available_bytes: disk.map(|disk| disk.available_space()),
+    }
+}
@@ -0,0 +1,268 @@
+//! Interactive shells running in pseudo-terminals.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use portable_pty::{ChildKiller, CommandBuilder, MasterPty, PtySize, native_pty_system};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::error::{CapabilityError, Result};
+
+const READ_BUFFER_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerminalConfig {
+    /// Maximum number of terminals open at once across all clients.
+    pub max_sessions: usize,
+    /// Shell to launch; defaults to the user's login shell.
+    pub shell: Option<String>,
+}
+
+impl Default for TerminalConfig {
+    fn default() -> Self {
+        Self {
+            max_sessions: 8,
+            shell: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TerminalSize {
+    pub cols: u16,
+    pub rows: u16,
+}
+
+impl Default for TerminalSize {
+    fn default() -> Self {
+        Self { cols: 80, rows: 24 }
+    }
+}
+
+impl From<TerminalSize> for PtySize {
+    fn from(size: TerminalSize) -> Self {
+        PtySize {
+            rows: size.rows.max(1),
+            cols: size.cols.max(1),
+            pixel_width: 0,
+            pixel_height: 0,
+        }
+    }
+}
+
+/// Output from a terminal, tagged with the terminal it came from so one
+/// channel can serve several terminals.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TerminalEvent {
+    TerminalOutput {
+        id: u64,
+        data: String,
+    },
+    /// The shell exited; no further output follows.
+    TerminalExit {
+        id: u64,
+        exit_code: Option<u32>,
+    },
+}
+
+/// Spawns terminals and enforces the session limit.
+pub struct TerminalManager {
+    config: TerminalConfig,
+    active: Arc<AtomicUsize>,
+    next_id: AtomicU64,
+}
+
+impl TerminalManager {
+    pub fn new(config: TerminalConfig) -> Self {
+        Self {
+            config,
+            active: Arc::new(AtomicUsize::new(0)),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    pub fn config(&self) -> &TerminalConfig {
+        &self.config
+    }
+
+    pub fn active_sessions(&self) -> usize {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    /// Starts a shell in `cwd`, which must already be resolved against the
+    /// allowed roots. Output and the exit notification are delivered on
+    /// `events`. The shell is hung up when the returned [`Terminal`] drops.
+    pub fn open(
+        &self,
+        cwd: &Path,
+        size: TerminalSize,
+        events: mpsc::Sender<TerminalEvent>,
+    ) -> Result<Terminal> {
+        if !cwd.is_dir() {
+            return Err(CapabilityError::InvalidArgument(format!(
+                "not a directory: {}",
+                cwd.display()
+            )));
+        }
+        let slot = SessionSlot::acquire(&self.active, self.config.max_sessions)?;
+
+        let pair = native_pty_system()
+            .openpty(size.into())
+            .map_err(terminal_error)?;
+        let mut command = match &self.config.shell {
+            Some(shell) => CommandBuilder::new(shell),
+            None => CommandBuilder::new_default_prog(),
+        };
+        command.cwd(cwd);
+        command.env("TERM", "xterm-256color");
+        let mut child = pair.slave.spawn_command(command).map_err(terminal_error)?;
+        // The child holds its own copy; keeping ours would stop reads from
+        // reporting end-of-file once the shell exits.
+        drop(pair.slave);
+
+        let killer = child.clone_killer();
+        let reader = pair.master.try_clone_reader().map_err(terminal_error)?;
+        let writer = pair.master.take_writer().map_err(terminal_error)?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        std::thread::spawn(move || {
+            pump_output(id, reader, &events);
+            let exit_code = child.wait().ok().map(|status| status.exit_code());
+            let _ = events.blocking_send(TerminalEvent::TerminalExit { id, exit_code });
+        });
+
+        let (input, input_rx) = std::sync::mpsc::channel::<Vec<u8>>();
+        std::thread::spawn(move || pump_input(writer, input_rx));
+
+        Ok(Terminal {
+            id,
+            cwd: cwd.to_path_buf(),
+            master: pair.master,
+            input,
+            killer,
+            _slot: slot,
+        })
+    }
+}
+
+/// A running shell. Dropping it hangs up the shell and frees its session.
+pub struct Terminal {
+    id: u64,
+    cwd: PathBuf,
+    master: Box<dyn MasterPty + Send>,
+    input: std::sync::mpsc::Sender<Vec<u8>>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+    _slot: SessionSlot,
+}
+
+impl Terminal {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn cwd(&self) -> &Path {
+        &self.cwd
+    }
+
+    /// Queues `data` as keyboard input. Never blocks, even when the shell
+    /// is not reading.
+    pub fn write(&self, data: &[u8]) -> Result<()> {
+        self.input
+            .send(data.to_vec())
+            .map_err(|_| CapabilityError::Terminal("terminal input is closed".into()))
+    }
+
+    pub fn resize(&self, size: TerminalSize) -> Result<()> {
+        self.master.resize(size.into()).map_err(terminal_error)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = self.killer.kill();
+    }
+}
+
+/// Counts towards the session limit until dropped.
+struct SessionSlot(Arc<AtomicUsize>);
+
+impl SessionSlot {
+    fn acquire(active: &Arc<AtomicUsize>, limit: usize) -> Result<Self> {
+        active
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                (count < limit).then_some(count + 1)
+            })
+            .map_err(|_| CapabilityError::TerminalLimit { limit })?;
+        Ok(Self(active.clone()))
+    }
+}
+
+impl Drop for SessionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Forwards PTY output as UTF-8 text until the shell exits or `events` is
+/// closed. Multi-byte characters split across reads are held back until
+/// complete.
+fn pump_output(id: u64, mut reader: Box<dyn Read + Send>, events: &mpsc::Sender<TerminalEvent>) {
+    let mut buffer = vec![0; READ_BUFFER_BYTES];
+    let mut pending = Vec::new();
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => read,
+        };
+        pending.extend_from_slice(&buffer[..read]);
+        let data = take_utf8(&mut pending);
+        if data.is_empty() {
+            continue;
+        }
+        if events
+            .blocking_send(TerminalEvent::TerminalOutput { id, data })
+            .is_err()
+        {
+            break;
+        }
+    }
+    if !pending.is_empty() {
+        let data = String::from_utf8_lossy(&pending).into_owned();
+        let _ = events.blocking_send(TerminalEvent::TerminalOutput { id, data });
+    }
+}
+
+/// Decodes `pending`, leaving an incomplete trailing character in place.
+/// Invalid sequences are replaced rather than held back.
+fn take_utf8(pending: &mut Vec<u8>) -> String {
+    let complete = match std::str::from_utf8(pending) {
+        Ok(_) => pending.len(),
+        Err(err) if err.error_len().is_none() => err.valid_up_to(),
+        Err(_) => pending.len(),
+    };
+    let rest = pending.split_off(complete);
+    let data = String::from_utf8_lossy(pending).into_owned();
+    *pending = rest;
+    data
+}
+
+fn pump_input(mut writer: Box<dyn Write + Send>, input: std::sync::mpsc::Receiver<Vec<u8>>) {
+    for data in input {
+        if writer
+            .write_all(&data)
+            .and_then(|()| writer.flush())
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+fn terminal_error(err: impl std::fmt::Display) -> CapabilityError {
+    CapabilityError::Terminal(err.to_string())
+}
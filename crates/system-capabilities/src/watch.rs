@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use notify::event::ModifyKind;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tokio::sync::{broadcast, mpsc};
+
+use crate::error::{CapabilityError, Result};
+
+const BROADCAST_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+    Renamed,
+}
+
+/// A debounced change to a path under a watched directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileSystemChanged {
+    pub path: PathBuf,
+    pub kind: ChangeKind,
+}
+
+/// Recursive directory watcher shared by all clients. Each directory is
+/// registered with the OS once, however many clients watch it, and changes
+/// are coalesced per path over the debounce window before being published.
+pub struct FileWatcher {
+    debounce: Duration,
+    events: broadcast::Sender<FileSystemChanged>,
+    inner: Mutex<Option<Inner>>,
+}
+
+struct Inner {
+    watcher: RecommendedWatcher,
+    watch_counts: HashMap<PathBuf, usize>,
+}
+
+impl FileWatcher {
+    pub fn new(debounce: Duration) -> Self {
+        let (events, _) = broadcast::channel(BROADCAST_CAPACITY);
+        Self {
+            debounce,
+            events,
+            inner: Mutex::new(None),
+        }
+    }
+
+    /// Receives changes for every watched directory; subscribers filter by
+    /// the paths they asked for.
+    pub fn subscribe(&self) -> broadcast::Receiver<FileSystemChanged> {
+        self.events.subscribe()
+    }
+
+    /// Starts watching `path` recursively. `path` must already be resolved
+    /// against the allowed roots. Must be called within a Tokio runtime.
+    pub fn watch(&self, path: &Path) -> Result<()> {
+        let mut guard = self.inner.lock().unwrap();
+        let inner = match guard.as_mut() {
+            Some(inner) => inner,
+            None => guard.insert(self.start()?),
+        };
+
+        match inner.watch_counts.entry(path.to_path_buf()) {
+            Entry::Occupied(mut entry) => *entry.get_mut() += 1,
+            Entry::Vacant(entry) => {
+                inner
+                    .watcher
+                    .watch(path, RecursiveMode::Recursive)
+                    .map_err(watch_error)?;
+                entry.insert(1);
+            }
+        }
+        Ok(())
+    }
+
+    /// Releases one [`watch`](Self::watch) of `path`.
+    pub fn unwatch(&self, path: &Path) {
+        let mut guard = self.inner.lock().unwrap();
+        let Some(inner) = guard.as_mut() else {
+            return;
+        };
+        let Some(count) = inner.watch_counts.get_mut(path) else {
+            return;
+        };
+
+        *count -= 1;
+        if *count == 0 {
+            inner.watch_counts.remove(path);
+            if let Err(err) = inner.watcher.unwatch(path) {
+                tracing::debug!("failed to unwatch {}: {err}", path.display());
+            }
+        }
+    }
+
+    fn start(&self) -> Result<Inner> {
+        let (raw_tx, raw_rx) = mpsc::unbounded_channel();
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = raw_tx.send(event);
+        })
+        .map_err(watch_error)?;
+
+        tokio::spawn(debounce(raw_rx, self.events.clone(), self.debounce));
+
+        Ok(Inner {
+            watcher,
+            watch_counts: HashMap::new(),
+        })
+    }
+}
+
+async fn debounce(
+    mut raw: mpsc::UnboundedReceiver<notify::Result<notify::Event>>,
+    events: broadcast::Sender<FileSystemChanged>,
+    window: Duration,
+) {
+    let mut pending: HashMap<PathBuf, ChangeKind> = HashMap::new();
+
+    while let Some(first) = raw.recv().await {
+        collect(&mut pending, first);
+
+        let deadline = tokio::time::sleep(window);
+        tokio::pin!(deadline);
+        loop {
+            tokio::select! {
+                _ = &mut deadline => break,
+                next = raw.recv() => match next {
+                    Some(event) => collect(&mut pending, event),
+                    None => break,
+                },
+            }
+        }
+
+        for (path, kind) in pending.drain() {
+            let _ = events.send(FileSystemChanged { path, kind });
+        }
+    }
+}
+
+fn collect(pending: &mut HashMap<PathBuf, ChangeKind>, event: notify::Result<notify::Event>) {
+    let event = match event {
+        Ok(event) => event,
+        Err(err) => {
+            tracing::warn!("file watcher error: {err}");
+            return;
+        }
+    };
+
+    let kind = match event.kind {
+        EventKind::Create(_) => ChangeKind::Created,
+        EventKind::Modify(ModifyKind::Name(_)) => ChangeKind::Renamed,
+        EventKind::Modify(_) => ChangeKind::Modified,
+        EventKind::Remove(_) => ChangeKind::Removed,
+        _ => return,
+    };
+
+    for path in event.paths {
+        pending
+            .entry(path)
+            .and_modify(|existing| {
+                // A file created and then written within the window is still
+                // reported as created.
+                if !(*existing == ChangeKind::Created && kind == ChangeKind::Modified) {
+                    *existing = kind;
+                }
+            })
+            .or_insert(kind);
+    }
+}
+
+fn watch_error(err: notify::Error) -> CapabilityError {
+    match err.kind {
+        notify::ErrorKind::Io(io) => CapabilityError::Io(io),
+        notify::ErrorKind::PathNotFound => {
+            CapabilityError::NotFound(err.paths.into_iter().next().unwrap_or_default())
+        }
+        _ => CapabilityError::Io(std::io::Error::other(err.to_string())),
+    }
+}